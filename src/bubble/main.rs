@@ -42,6 +42,8 @@ pub struct Args {
 
 fn main() -> IoResult<()> {
     let args = Args::parse();
+    let charset = args.common.charset();
+    let theme = args.common.theme();
 
     setup_term()?;
 
@@ -64,10 +66,23 @@ fn main() -> IoResult<()> {
             Ordering::Less
         };
 
-        let colors = [
-            args.color1.unwrap_or_else(|| rand.random_range(0..8) as u8) % 8,
-            args.color2.unwrap_or_else(|| rand.random_range(1..8) as u8) % 8,
-        ];
+        // Choose colors so that active bars can be told apart from inactive
+        // ones in the selected palette.
+        let color1 = match args.color1 {
+            Some(color) => color as usize % 8,
+            None => {
+                let avoid = args
+                    .color2
+                    .map(|c| vec![c as usize % 8])
+                    .unwrap_or_default();
+                theme.choose_color(&mut rand, 0..8, &avoid)
+            }
+        };
+        let color2 = match args.color2 {
+            Some(color) => color as usize % 8,
+            None => theme.choose_color(&mut rand, 1..8, &[color1]),
+        };
+        let colors = [color1 as u8, color2 as u8];
 
         let style = match args.style.unwrap_or_else(|| rand.random_range(0..4)) % 4 {
             0 => RenderStyle::Block,
@@ -89,9 +104,18 @@ fn main() -> IoResult<()> {
             direction = !direction;
             sorted = true;
             for i in 0..(width - 1) {
-                while !renderer::render(&mut displayed, &actual, width, height, colors, style)? {
+                while !renderer::render(
+                    &mut displayed,
+                    &actual,
+                    width,
+                    height,
+                    colors,
+                    style,
+                    charset,
+                    theme,
+                )? {
                     match args.common.wait()? {
-                        WaitResult::Continue => {}
+                        WaitResult::Continue | WaitResult::Key(_) | WaitResult::Mouse(_) => {}
                         WaitResult::Resize(_, _) => continue 'outer,
                         WaitResult::Exit => break 'outer,
                     }
@@ -111,10 +135,19 @@ fn main() -> IoResult<()> {
         }
 
         for _ in 0..32 {
-            renderer::render(&mut displayed, &actual, width, height, colors, style)?;
+            renderer::render(
+                &mut displayed,
+                &actual,
+                width,
+                height,
+                colors,
+                style,
+                charset,
+                theme,
+            )?;
 
             match args.common.wait()? {
-                WaitResult::Continue => {}
+                WaitResult::Continue | WaitResult::Key(_) | WaitResult::Mouse(_) => {}
                 WaitResult::Resize(_, _) => continue 'outer,
                 WaitResult::Exit => break 'outer,
             }
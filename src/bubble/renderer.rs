@@ -5,7 +5,10 @@ use std::io::{Result as IoResult, Write, stdout};
 
 use bitvec::bitvec;
 use crossterm::{cursor::MoveTo, queue, style::PrintStyledContent};
-use doodles::common::term::{DIM_STYLES, STYLES};
+use doodles::common::{
+    term::Charset,
+    theme::{Intensity, Theme},
+};
 
 #[derive(Clone, Copy)]
 pub enum RenderStyle {
@@ -21,7 +24,10 @@ const DOT_GLYPHS_ASC: [&str; 9] = [" ", "⢀", "⣀", "⣠", "⣤", "⣴", "⣶"
 const DOT_GLYPHS_DESC: [&str; 9] = [" ", "⡀", "⣀", "⣄", "⣤", "⣦", "⣶", "⣷", "⣿"];
 const FRACTION_GLYPHS: [&str; 9] = ["0", "⅛", "¼", "⅜", "½", "⅝", "¾", "⅞", "1"];
 const OCTAL_GLYPHS: [&str; 9] = ["0", "1", "2", "3", "4", "5", "6", "7", "8"];
+const ASCII_BLOCK_GLYPHS: [&str; 9] = [" ", "_", ".", ",", "-", "=", "+", "*", "#"];
+const ASCII_DOT_GLYPHS: [&str; 9] = [" ", ".", ".", ":", ":", "i", "i", "|", "|"];
 
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     displayed: &mut [usize],
     actual: &[usize],
@@ -29,18 +35,22 @@ pub fn render(
     height: usize,
     colors: [u8; 2],
     style: RenderStyle,
+    charset: Charset,
+    theme: Theme,
 ) -> IoResult<bool> {
     let mut stdout = stdout();
 
     let mut converged = true;
     let mut changed = bitvec![0; width];
 
-    let glyphs = match style {
-        RenderStyle::Block => BLOCK_GLYPHS,
-        RenderStyle::DotsAsc => DOT_GLYPHS_ASC,
-        RenderStyle::DotsDesc => DOT_GLYPHS_DESC,
-        RenderStyle::Fraction => FRACTION_GLYPHS,
-        RenderStyle::Octal => OCTAL_GLYPHS,
+    let glyphs = match (style, charset) {
+        (RenderStyle::Block, Charset::Unicode) => BLOCK_GLYPHS,
+        (RenderStyle::DotsAsc, Charset::Unicode) => DOT_GLYPHS_ASC,
+        (RenderStyle::DotsDesc, Charset::Unicode) => DOT_GLYPHS_DESC,
+        (RenderStyle::Fraction, Charset::Unicode) => FRACTION_GLYPHS,
+        (RenderStyle::Block, Charset::Ascii) => ASCII_BLOCK_GLYPHS,
+        (RenderStyle::DotsAsc | RenderStyle::DotsDesc, Charset::Ascii) => ASCII_DOT_GLYPHS,
+        (RenderStyle::Fraction | RenderStyle::Octal, _) => OCTAL_GLYPHS,
     };
 
     for x in 0..width {
@@ -72,16 +82,19 @@ pub fn render(
             let frac = value % 8;
             let whole = value / 8;
 
-            let styles = if y < whole || (y == whole && frac > 0) {
-                &STYLES
+            // Without colour, active bars are distinguished by being bold.
+            let intensity = if !(y < whole || (y == whole && frac > 0)) {
+                Intensity::Dim
+            } else if changed[x] && theme.is_monochrome() {
+                Intensity::Bold
             } else {
-                &DIM_STYLES
+                Intensity::Normal
             };
 
             let style = if changed[x] {
-                styles[(colors[1] as usize) % styles.len()]
+                theme.style(colors[1] as usize, intensity)
             } else {
-                styles[(colors[0] as usize) % styles.len()]
+                theme.style(colors[0] as usize, intensity)
             };
 
             let glyph = if y < whole {
@@ -4,3 +4,4 @@
 pub mod borders;
 pub mod dir;
 pub mod term;
+pub mod theme;
@@ -81,6 +81,7 @@ impl Directions {
     pub fn border(self, vertical_style: BorderStyle, horizontal_style: BorderStyle) -> char {
         let borders =
             match (vertical_style, horizontal_style) {
+                (BorderStyle::Ascii, _) | (_, BorderStyle::Ascii) => &BORDERS_ASCII,
                 (BorderStyle::Single, BorderStyle::Single) => &BORDERS_SINGLE,
                 (BorderStyle::Curved, BorderStyle::Curved) => &BORDERS_CURVED,
                 (BorderStyle::Bold, BorderStyle::Bold) => &BORDERS_BOLD,
@@ -100,9 +101,9 @@ impl Directions {
     }
 }
 
-impl Into<Directions> for Direction {
-    fn into(self) -> Directions {
-        match self {
+impl From<Direction> for Directions {
+    fn from(dir: Direction) -> Directions {
+        match dir {
             Direction::North => Directions::NORTH,
             Direction::East => Directions::EAST,
             Direction::South => Directions::SOUTH,
@@ -111,11 +112,11 @@ impl Into<Directions> for Direction {
     }
 }
 
-impl TryInto<Direction> for Directions {
+impl TryFrom<Directions> for Direction {
     type Error = ();
 
-    fn try_into(self) -> Result<Direction, Self::Error> {
-        match self {
+    fn try_from(dirs: Directions) -> Result<Direction, Self::Error> {
+        match dirs {
             Directions::NORTH => Ok(Direction::North),
             Directions::EAST => Ok(Direction::East),
             Directions::SOUTH => Ok(Direction::South),
@@ -2,21 +2,27 @@
 // Licensed under the MIT-0 license.
 
 use std::{
-    io::{Result as IoResult, stderr, stdout},
-    time::Duration,
+    env,
+    io::{Result as IoResult, Write, stderr, stdout},
+    time::{Duration, Instant},
 };
 
 use clap::Parser;
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
-    event::{self, Event, KeyCode},
-    execute,
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseEvent, MouseEventKind,
+    },
+    execute, queue,
     style::{Attribute, Attributes, Color, ContentStyle, PrintStyledContent},
     terminal::{
         Clear, ClearType, DisableLineWrap, EnableLineWrap, EnterAlternateScreen,
         LeaveAlternateScreen,
     },
 };
+use unicode_width::UnicodeWidthChar;
+
+use crate::common::theme::{Background, Palette, Theme, no_color};
 
 #[derive(Parser, Debug)]
 pub struct CommonArgs {
@@ -41,8 +47,71 @@ pub struct CommonArgs {
         conflicts_with = "interactive"
     )]
     wait: u64,
+
+    /// Draw using only ASCII characters.
+    ///
+    /// By default, the locale (`LC_ALL`, `LC_CTYPE` or `LANG`) and `TERM`
+    /// environment variables are inspected to decide whether the terminal can
+    /// display braille, box-drawing and block glyphs. This option forces the
+    /// ASCII fallback regardless of what is detected.
+    #[arg(long)]
+    ascii: bool,
+
+    /// Draw without colour.
+    ///
+    /// Colours are replaced with text attributes such as bold, dim, reverse
+    /// and underline, and some programs use different glyph shapes instead.
+    /// This is implied if the `NO_COLOR` environment variable is set to a
+    /// non-empty value.
+    #[arg(long)]
+    monochrome: bool,
+
+    /// Colour palette to draw with.
+    ///
+    /// The colour-blind palettes replace the standard terminal colours with
+    /// ones that remain distinguishable, and randomly chosen colours are
+    /// picked so that pairs that are easily confused never appear together.
+    #[arg(long, value_enum, default_value_t = Palette::Default)]
+    palette: Palette,
+
+    /// Reduce motion.
+    ///
+    /// Frames are drawn no more often than every 250 milliseconds, and effects
+    /// that make glyphs flicker are disabled.
+    #[arg(long)]
+    reduced_motion: bool,
+
+    /// Brightness of the terminal background.
+    ///
+    /// Colours are tuned to stand out against the background. If not
+    /// specified, the terminal is asked for its background colour, falling
+    /// back to the `COLORFGBG` environment variable and then to a dark
+    /// background.
+    #[arg(long, value_enum)]
+    background: Option<Background>,
 }
 
+/// The set of characters that renderers may draw with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Charset {
+    /// Any Unicode character, including braille, box-drawing and block glyphs.
+    Unicode,
+
+    /// Printable 7-bit ASCII characters only.
+    Ascii,
+}
+
+/// Minimum delay between frames in milliseconds when motion is reduced.
+const REDUCED_MOTION_WAIT: u64 = 250;
+
+/// Half-width katakana voiced and semi-voiced sound marks.
+const HALFWIDTH_SOUND_MARKS: [char; 2] = ['\u{FF9E}', '\u{FF9F}'];
+
+/// Terminal types known to lack glyphs beyond ASCII.
+const ASCII_TERMS: [&str; 8] = [
+    "dumb", "linux", "cons25", "vt52", "vt100", "vt102", "vt220", "vt320",
+];
+
 pub fn validate_color(s: &str) -> Result<usize, String> {
     match s.parse::<usize>() {
         Ok(n) if n <= 7 => Ok(n),
@@ -50,10 +119,262 @@ pub fn validate_color(s: &str) -> Result<usize, String> {
     }
 }
 
+impl Charset {
+    /// Guesses the character set supported by the terminal from the
+    /// environment.
+    ///
+    /// The first non-empty value of `LC_ALL`, `LC_CTYPE` and `LANG` must name a
+    /// UTF-8 locale, and `TERM` must not name a console known to lack Unicode
+    /// glyphs (such as the Linux virtual console or a VT-series serial
+    /// terminal). On Windows, where these variables are rarely set, the
+    /// locale check is skipped.
+    pub fn detect() -> Self {
+        let term = env::var("TERM").unwrap_or_default();
+        let limited_term = ASCII_TERMS.iter().any(|prefix| term.starts_with(prefix));
+
+        if limited_term || !locale_is_utf8() {
+            Charset::Ascii
+        } else {
+            Charset::Unicode
+        }
+    }
+
+    /// Returns `true` if only ASCII characters may be drawn.
+    pub fn is_ascii(self) -> bool {
+        self == Charset::Ascii
+    }
+}
+
+/// Returns the number of terminal columns occupied by `ch`.
+///
+/// Most glyphs occupy a single column, but East Asian full-width characters
+/// (such as CJK ideographs and full-width katakana) occupy two. Half-width
+/// katakana occupy one. Returns `None` for characters that cannot be drawn on
+/// their own, such as control characters and zero-width combining marks.
+///
+/// The half-width katakana voiced sound marks (`ﾞ` and `ﾟ`) are treated as
+/// ordinary single-column glyphs. Although Unicode classes them as modifiers,
+/// terminals draw them in a column of their own.
+pub fn glyph_width(ch: char) -> Option<usize> {
+    if HALFWIDTH_SOUND_MARKS.contains(&ch) {
+        return Some(1);
+    }
+
+    match UnicodeWidthChar::width(ch) {
+        Some(0) | None => None,
+        width => width,
+    }
+}
+
+/// Queues a styled glyph at the current cursor position.
+///
+/// This should be used instead of printing glyphs directly whenever the glyph
+/// may come from user input, so that every glyph takes up a predictable number
+/// of columns.
+///
+/// Arguments
+/// =========
+///
+/// - `out` - The output to queue the glyph on.
+/// - `style` - The style to apply to the glyph.
+/// - `ch` - The glyph to print.
+/// - `columns` - The number of columns remaining in the current row.
+///
+/// Returns
+/// =======
+///
+/// The number of columns the glyph occupies. Wide glyphs occupy two columns,
+/// so the caller should skip the following cell. If a wide glyph does not fit
+/// in the remaining columns, or the glyph has no width at all, a space is
+/// printed instead and one column is consumed.
+pub fn queue_glyph<W: Write>(
+    out: &mut W,
+    style: ContentStyle,
+    ch: char,
+    columns: usize,
+) -> IoResult<usize> {
+    match glyph_width(ch) {
+        Some(width) if width <= columns => {
+            queue!(out, PrintStyledContent(style.apply(ch)))?;
+            Ok(width)
+        }
+        _ => {
+            queue!(out, PrintStyledContent(style.apply(' ')))?;
+            Ok(1)
+        }
+    }
+}
+
+/// Asks the terminal for its background colour using an OSC 11 query.
+///
+/// The query is followed by a primary device attributes (DA1) query, which
+/// every terminal answers, so that an answer to the first query can only
+/// arrive before the answer to the second. Reading up to the second answer
+/// leaves no reply behind to be mistaken for key presses later.
+///
+/// Returns the red, green and blue components in the range 0 to 1, or `None`
+/// if standard input and output are not both a terminal, the terminal does
+/// not support the query, or it did not answer within [`QUERY_TIMEOUT`].
+#[cfg(unix)]
+pub fn query_background_color() -> Option<(f64, f64, f64)> {
+    use std::{
+        io::{IsTerminal, stdin},
+        os::fd::AsRawFd,
+    };
+
+    use crossterm::terminal;
+
+    if !stdin().is_terminal() || !stdout().is_terminal() {
+        return None;
+    }
+
+    // Raw mode stops the answer from being echoed and lets it be read before
+    // a newline arrives.
+    let was_raw = terminal::is_raw_mode_enabled().ok()?;
+    if !was_raw {
+        terminal::enable_raw_mode().ok()?;
+    }
+
+    let mut stdout = stdout();
+    let response = write!(stdout, "\x1b]11;?\x1b\\\x1b[c")
+        .and_then(|_| stdout.flush())
+        .ok()
+        .and_then(|_| read_query_responses(stdin().as_raw_fd()));
+
+    if !was_raw {
+        _ = terminal::disable_raw_mode();
+    }
+
+    parse_osc_color(&response?)
+}
+
+#[cfg(not(unix))]
+pub fn query_background_color() -> Option<(f64, f64, f64)> {
+    None
+}
+
+/// How long to wait for the terminal to answer a query. Terminals answer
+/// at once, so this only runs out on very slow links or if the terminal does
+/// not answer device attributes queries.
+#[cfg(unix)]
+const QUERY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Reads the answers to queries from `fd` up to and including the answer to
+/// a device attributes query, `ESC [ ? ... c`, one byte at a time so that no
+/// input beyond it is consumed.
+///
+/// Returns the answers before the device attributes, or `None` if it did not
+/// arrive in time, in which case any input waiting to be read is discarded so
+/// that a late answer is not read as key presses.
+#[cfg(unix)]
+fn read_query_responses(fd: i32) -> Option<String> {
+    use std::time::Instant;
+
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut response = Vec::new();
+
+    let discard = || {
+        // SAFETY: `tcflush` only discards input queued on the descriptor.
+        unsafe { libc::tcflush(fd, libc::TCIFLUSH) };
+        None
+    };
+
+    while response.len() < 256 {
+        let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+            return discard();
+        };
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        // SAFETY: `pollfd` is a valid, initialized `pollfd` structure and the
+        // count passed is 1.
+        let ready = unsafe { libc::poll(&mut pollfd, 1, remaining.as_millis() as i32) };
+        if ready <= 0 {
+            return discard();
+        }
+
+        let mut byte = 0u8;
+        // SAFETY: `byte` is valid for writes of one byte.
+        if unsafe { libc::read(fd, (&mut byte as *mut u8).cast(), 1) } != 1 {
+            return discard();
+        }
+
+        response.push(byte);
+        if let Some(start) = device_attributes_start(&response) {
+            response.truncate(start);
+            return String::from_utf8(response).ok();
+        }
+    }
+
+    discard()
+}
+
+/// Returns the position at which a complete device attributes answer,
+/// `ESC [ ? 6 2 ; 2 2 c` or similar, ends `response`, if it does.
+#[cfg(unix)]
+fn device_attributes_start(response: &[u8]) -> Option<usize> {
+    let (&last, rest) = response.split_last()?;
+    if last != b'c' {
+        return None;
+    }
+
+    let params = rest
+        .iter()
+        .rev()
+        .take_while(|&&byte| byte.is_ascii_digit() || byte == b';')
+        .count();
+    let start = rest.len().checked_sub(params + 3)?;
+    (&rest[start..start + 3] == b"\x1b[?").then_some(start)
+}
+
+/// Parses the colour in an OSC colour response such as
+/// `ESC ] 11 ; rgb:ffff/ffff/dddd BEL`.
+///
+/// Each component may have from one to four hexadecimal digits and is scaled
+/// to the range 0 to 1.
+#[cfg(unix)]
+fn parse_osc_color(response: &str) -> Option<(f64, f64, f64)> {
+    let (_, rgb) = response.split_once("rgb:")?;
+    let rgb = rgb.trim_end_matches(['\x07', '\x1b', '\\']);
+
+    let mut components = rgb.split('/').map(|hex| {
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        let max = (1u32 << (4 * hex.len().clamp(1, 4))) - 1;
+        Some(value as f64 / max as f64)
+    });
+
+    let r = components.next()??;
+    let g = components.next()??;
+    let b = components.next()??;
+    Some((r, g, b))
+}
+
+#[cfg(windows)]
+fn locale_is_utf8() -> bool {
+    true
+}
+
+#[cfg(not(windows))]
+fn locale_is_utf8() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum WaitResult {
     Continue,
     Resize(usize, usize),
+    Key(KeyCode),
+    Mouse(MouseEvent),
     Exit,
 }
 
@@ -219,9 +540,18 @@ pub const ERROR_STYLE: ContentStyle = ContentStyle {
 
 /// Prints a formatted error message to the standard error output.
 pub fn error(msg: &str) {
+    let style = if no_color() {
+        ContentStyle {
+            foreground_color: None,
+            ..ERROR_STYLE
+        }
+    } else {
+        ERROR_STYLE
+    };
+
     _ = execute!(
         stderr(),
-        PrintStyledContent(ERROR_STYLE.apply("Error: ")),
+        PrintStyledContent(style.apply("Error: ")),
         PrintStyledContent(ContentStyle::default().apply(msg)),
     );
 }
@@ -236,9 +566,16 @@ pub fn setup_term() -> IoResult<()> {
     )
 }
 
+/// Starts reporting mouse presses and drags as `WaitResult::Mouse`, for
+/// programs that can be drawn on. Capture ends when the terminal is cleaned up.
+pub fn capture_mouse() -> IoResult<()> {
+    execute!(stdout(), EnableMouseCapture)
+}
+
 pub fn cleanup_term() -> IoResult<()> {
     execute!(
         stdout(),
+        DisableMouseCapture,
         Clear(ClearType::All),
         MoveTo(0, 0),
         Show,
@@ -266,6 +603,43 @@ macro_rules! abort {
 }
 
 impl CommonArgs {
+    /// Returns the theme renderers should use, honouring `--monochrome`,
+    /// `NO_COLOR`, `--palette` and `--background`.
+    ///
+    /// This may query the terminal, so it must be called before
+    /// [`setup_term`]. Monochrome themes ignore the background, so the
+    /// terminal is not queried for them.
+    pub fn theme(&self) -> Theme {
+        let theme = if self.monochrome {
+            Theme::monochrome()
+        } else {
+            Theme::default()
+        };
+
+        let background = match self.background {
+            Some(background) => background,
+            None if theme.is_monochrome() => Background::default(),
+            None => Background::detect(),
+        };
+        theme.with_palette(self.palette).with_background(background)
+    }
+
+    /// Returns `true` if the user asked for reduced motion, in which case
+    /// renderers should avoid flickering effects.
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+
+    /// Returns the character set renderers should use, honouring `--ascii`
+    /// before falling back to [`Charset::detect`].
+    pub fn charset(&self) -> Charset {
+        if self.ascii {
+            Charset::Ascii
+        } else {
+            Charset::detect()
+        }
+    }
+
     /// Wait for either a delay to elapse or a keypress event, depending on the
     /// arguments.
     ///
@@ -275,33 +649,121 @@ impl CommonArgs {
     /// - `WaitResult::Continue(Some(Event))` if an event was detected.
     /// - `WaitResult::Continue(None)` if the wait time elapsed without events
     ///   and [`CommonArgs::interactive`] is false.
+    /// - `WaitResult::Mouse` if a mouse button was pressed, dragged or
+    ///   released, once [`capture_mouse`] has been called.
     /// - `WaitResult::Exit` if the user requested to exit (Esc or 'q' key).
     pub fn wait(&self) -> IoResult<WaitResult> {
+        self.wait_keys(&[])
+    }
+
+    /// Like [`CommonArgs::wait`], but lets the program handle some keys itself.
+    ///
+    /// Pressing any of the keys in `keys` returns `WaitResult::Key` with that
+    /// key instead of continuing or exiting. Esc and 'q' always exit, even if
+    /// they are listed.
+    pub fn wait_keys(&self, keys: &[KeyCode]) -> IoResult<WaitResult> {
+        self.wait_keys_until(keys, self.frame_deadline())
+    }
+
+    /// Returns the time at which a frame starting now should end.
+    pub fn frame_deadline(&self) -> Instant {
+        let wait = if self.reduced_motion {
+            self.wait.max(REDUCED_MOTION_WAIT)
+        } else {
+            self.wait
+        };
+        Instant::now() + Duration::from_millis(wait)
+    }
+
+    /// Like [`CommonArgs::wait_keys`], but waits until the given deadline (see
+    /// [`CommonArgs::frame_deadline`]), so that programs can handle events
+    /// without restarting the frame.
+    pub fn wait_keys_until(&self, keys: &[KeyCode], deadline: Instant) -> IoResult<WaitResult> {
+        // Events that are ignored, such as mouse movements, do not cut the
+        // frame short.
         loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
             let result = if self.interactive {
-                self.handle_event()?
-            } else if let Ok(true) = event::poll(Duration::from_millis(self.wait)) {
-                self.handle_event()?
+                self.handle_event(keys)?
+            } else if let Ok(true) = event::poll(remaining) {
+                self.handle_event(keys)?
             } else {
-                None
+                break Ok(WaitResult::Continue);
             };
 
-            match result {
-                Some(result) => return Ok(result),
-                None => {
-                    if self.interactive {
-                        continue;
-                    } else {
-                        break Ok(WaitResult::Continue);
-                    }
+            if let Some(result) = result {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Waits until one of the given keys is pressed, ignoring any others, for
+    /// programs that pause to let the user edit or inspect something.
+    ///
+    /// Returns
+    /// =======
+    ///
+    /// - `WaitResult::Key` with the key pressed.
+    /// - `WaitResult::Resize` if the terminal was resized.
+    /// - `WaitResult::Exit` if the user requested to exit (Esc or 'q' key).
+    pub fn read_key(&self, keys: &[KeyCode]) -> IoResult<WaitResult> {
+        loop {
+            match event::read()? {
+                Event::Key(ev)
+                    if ev.is_press() && [KeyCode::Esc, KeyCode::Char('q')].contains(&ev.code) =>
+                {
+                    return Ok(WaitResult::Exit);
+                }
+                Event::Key(ev) if ev.is_press() && keys.contains(&ev.code) => {
+                    return Ok(WaitResult::Key(ev.code));
+                }
+                Event::Resize(width, height) => {
+                    return Ok(WaitResult::Resize(width as usize, height as usize));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Waits for the given duration or until a key is pressed, for example to
+    /// give the user time to read a message. In interactive mode, this waits
+    /// for a key regardless of the duration.
+    ///
+    /// Returns
+    /// =======
+    ///
+    /// - `WaitResult::Exit` if the user requested to exit.
+    /// - `WaitResult::Continue` otherwise.
+    pub fn pause(&self, duration: Duration) -> IoResult<WaitResult> {
+        let deadline = Instant::now() + duration;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if !self.interactive && remaining.is_zero() {
+                return Ok(WaitResult::Continue);
+            }
+
+            if self.interactive || event::poll(remaining)? {
+                match self.handle_event(&[])? {
+                    Some(WaitResult::Exit) => return Ok(WaitResult::Exit),
+                    Some(WaitResult::Continue) => return Ok(WaitResult::Continue),
+                    _ => {}
                 }
             }
         }
     }
 
-    fn handle_event(&self) -> IoResult<Option<WaitResult>> {
+    fn handle_event(&self, keys: &[KeyCode]) -> IoResult<Option<WaitResult>> {
         if let Ok(event) = event::read() {
             match event {
+                Event::Key(ev)
+                    if ev.is_press()
+                        && keys.contains(&ev.code)
+                        && ev.code != KeyCode::Esc
+                        && ev.code != KeyCode::Char('q') =>
+                {
+                    Ok(Some(WaitResult::Key(ev.code)))
+                }
                 Event::Key(ev)
                     if ev.is_press()
                         && (!self.interactive
@@ -314,6 +776,14 @@ impl CommonArgs {
                 Event::Resize(width, height) => {
                     Ok(Some(WaitResult::Resize(width as usize, height as usize)))
                 }
+                Event::Mouse(ev)
+                    if matches!(
+                        ev.kind,
+                        MouseEventKind::Down(_) | MouseEventKind::Drag(_) | MouseEventKind::Up(_)
+                    ) =>
+                {
+                    Ok(Some(WaitResult::Mouse(ev)))
+                }
                 _ => Ok(None),
             }
         } else {
@@ -0,0 +1,432 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use std::{env, ops::Range};
+
+use clap::ValueEnum;
+use crossterm::style::{Attribute, Attributes, Color, ContentStyle};
+use rand::{Rng, seq::IteratorRandom};
+
+use crate::common::term::{BOLD_STYLES, DIM_STYLES, STYLES, query_background_color};
+
+/// How brightly a glyph should be drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Intensity {
+    Bold,
+    Normal,
+    Dim,
+}
+
+/// Named sets of colours that colours 0 to 7 are drawn with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Palette {
+    /// The terminal's own eight standard colours.
+    #[default]
+    Default,
+
+    /// Colours that remain distinct with reduced sensitivity to green light
+    /// (based on the Okabe-Ito palette).
+    Deuteranopia,
+
+    /// Colours that remain distinct with reduced sensitivity to red light
+    /// (based on the Okabe-Ito palette, with red replaced by orange).
+    Protanopia,
+
+    /// Colours that remain distinct with reduced sensitivity to blue light.
+    Tritanopia,
+
+    /// Bright colours without the dim attribute.
+    HighContrast,
+}
+
+/// The brightness of the terminal's background, which decides how colours are
+/// tuned so that they stand out from it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Background {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// Selects the styles renderers use to draw coloured glyphs.
+///
+/// Colours are indices from 0 to 7 into a [`Palette`], which by default maps
+/// them to the standard terminal colours, as accepted by
+/// [`crate::common::term::validate_color`]. Renderers should ask the theme for
+/// a style rather than indexing the style tables directly, so that the user's
+/// choice of colour mode and palette is respected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    monochrome: bool,
+    palette: Palette,
+    background: Background,
+}
+
+/// Bold, normal and dim variants of each colour in a palette, as 256-colour
+/// terminal values.
+///
+/// On dark backgrounds the variants run from bright to dark; on light
+/// backgrounds they run from dark to light, so that dim glyphs always fade
+/// towards the background.
+type PaletteColors = [[u8; 3]; 8];
+
+const DEUTERANOPIA_COLORS: PaletteColors = [
+    [250, 245, 240], // Grey
+    [202, 166, 130], // Vermillion
+    [36, 36, 29],    // Bluish green
+    [227, 221, 178], // Yellow
+    [32, 25, 24],    // Blue
+    [218, 175, 132], // Reddish purple
+    [117, 74, 67],   // Sky blue
+    [231, 255, 246], // White
+];
+
+const PROTANOPIA_COLORS: PaletteColors = [
+    [250, 245, 240], // Grey
+    [214, 214, 172], // Orange
+    [36, 36, 29],    // Bluish green
+    [227, 221, 178], // Yellow
+    [32, 25, 24],    // Blue
+    [218, 175, 132], // Reddish purple
+    [117, 74, 67],   // Sky blue
+    [231, 255, 246], // White
+];
+
+const TRITANOPIA_COLORS: PaletteColors = [
+    [250, 245, 240], // Grey
+    [196, 160, 124], // Red
+    [37, 30, 23],    // Teal
+    [218, 211, 168], // Pink
+    [93, 54, 53],    // Purple
+    [201, 163, 126], // Magenta
+    [51, 44, 37],    // Cyan
+    [231, 255, 246], // White
+];
+
+const LIGHT_DEFAULT_COLORS: PaletteColors = [
+    [232, 240, 248], // Grey
+    [124, 160, 217], // Red
+    [22, 28, 151],   // Green
+    [136, 178, 229], // Yellow
+    [18, 25, 153],   // Blue
+    [90, 127, 219],  // Magenta
+    [23, 30, 159],   // Cyan
+    [16, 236, 248],  // White (drawn as black)
+];
+
+const LIGHT_DEUTERANOPIA_COLORS: PaletteColors = [
+    [232, 240, 248], // Grey
+    [130, 166, 216], // Vermillion
+    [23, 29, 115],   // Bluish green
+    [136, 178, 229], // Yellow
+    [18, 25, 153],   // Blue
+    [89, 132, 218],  // Reddish purple
+    [24, 67, 117],   // Sky blue
+    [16, 236, 248],  // White (drawn as black)
+];
+
+const LIGHT_PROTANOPIA_COLORS: PaletteColors = [
+    [232, 240, 248], // Grey
+    [130, 172, 222], // Orange
+    [23, 29, 115],   // Bluish green
+    [136, 178, 229], // Yellow
+    [18, 25, 153],   // Blue
+    [89, 132, 218],  // Reddish purple
+    [24, 67, 117],   // Sky blue
+    [16, 236, 248],  // White (drawn as black)
+];
+
+const LIGHT_TRITANOPIA_COLORS: PaletteColors = [
+    [232, 240, 248], // Grey
+    [88, 160, 217],  // Red
+    [23, 30, 116],   // Teal
+    [162, 205, 225], // Pink
+    [54, 91, 183],   // Purple
+    [90, 163, 219],  // Magenta
+    [24, 31, 159],   // Cyan
+    [16, 236, 248],  // White (drawn as black)
+];
+
+const LIGHT_HIGH_CONTRAST_COLORS: PaletteColors = [
+    [16, 232, 240], // Grey
+    [88, 124, 160], // Red
+    [22, 22, 28],   // Green
+    [94, 130, 136], // Yellow
+    [17, 18, 19],   // Blue
+    [53, 90, 127],  // Magenta
+    [23, 23, 30],   // Cyan
+    [16, 16, 236],  // White (drawn as black)
+];
+
+const HIGH_CONTRAST_COLORS: PaletteColors = [
+    [255, 252, 248], // Grey
+    [196, 196, 160], // Red
+    [46, 46, 34],    // Green
+    [226, 226, 178], // Yellow
+    [33, 33, 27],    // Blue
+    [201, 201, 164], // Magenta
+    [51, 51, 37],    // Cyan
+    [231, 231, 252], // White
+];
+
+/// Attributes that stand in for each colour in monochrome mode when the colour
+/// carries meaning (see [`Theme::marked_style`]).
+const MONOCHROME_MARKS: [Attributes; 8] = [
+    Attributes::none(),
+    Attributes::none().with(Attribute::Reverse),
+    Attributes::none().with(Attribute::Underlined),
+    Attributes::none()
+        .with(Attribute::Reverse)
+        .with(Attribute::Underlined),
+    Attributes::none().with(Attribute::Reverse),
+    Attributes::none().with(Attribute::Underlined),
+    Attributes::none()
+        .with(Attribute::Reverse)
+        .with(Attribute::Underlined),
+    Attributes::none(),
+];
+
+impl Palette {
+    /// Returns `true` if colours `a` and `b` are likely to be confused with one
+    /// another when drawn from this palette.
+    ///
+    /// Identical colours are always considered to conflict.
+    pub fn conflicts(self, a: usize, b: usize) -> bool {
+        let (a, b) = (a % 8, b % 8);
+        let pairs: &[(usize, usize)] = match self {
+            // Red/green and green/yellow are hard to tell apart with most
+            // forms of colour blindness, as is blue/magenta with protanopia.
+            Palette::Default => &[(1, 2), (2, 3), (4, 5)],
+            Palette::Deuteranopia => &[(4, 5)],
+            Palette::Protanopia => &[(1, 3), (4, 5)],
+            Palette::Tritanopia => &[(2, 6), (1, 5)],
+            Palette::HighContrast => &[(1, 2), (2, 3)],
+        };
+
+        a == b || pairs.contains(&(a.min(b), a.max(b)))
+    }
+
+    fn colors(self, background: Background) -> Option<&'static PaletteColors> {
+        match (self, background) {
+            (Palette::Default, Background::Dark) => None,
+            (Palette::Deuteranopia, Background::Dark) => Some(&DEUTERANOPIA_COLORS),
+            (Palette::Protanopia, Background::Dark) => Some(&PROTANOPIA_COLORS),
+            (Palette::Tritanopia, Background::Dark) => Some(&TRITANOPIA_COLORS),
+            (Palette::HighContrast, Background::Dark) => Some(&HIGH_CONTRAST_COLORS),
+            (Palette::Default, Background::Light) => Some(&LIGHT_DEFAULT_COLORS),
+            (Palette::Deuteranopia, Background::Light) => Some(&LIGHT_DEUTERANOPIA_COLORS),
+            (Palette::Protanopia, Background::Light) => Some(&LIGHT_PROTANOPIA_COLORS),
+            (Palette::Tritanopia, Background::Light) => Some(&LIGHT_TRITANOPIA_COLORS),
+            (Palette::HighContrast, Background::Light) => Some(&LIGHT_HIGH_CONTRAST_COLORS),
+        }
+    }
+}
+
+impl Background {
+    /// Guesses the brightness of the terminal's background.
+    ///
+    /// The terminal is first asked for its background colour with an OSC 11
+    /// query. If it does not answer in time, the `COLORFGBG` environment
+    /// variable (set by rxvt, Konsole and others as `foreground;background`)
+    /// is consulted. If neither is available, a dark background is assumed.
+    ///
+    /// This must be called before the terminal is set up for drawing, as the
+    /// query briefly takes over the terminal's input.
+    pub fn detect() -> Self {
+        if let Some((r, g, b)) = query_background_color() {
+            // Relative luminance (ITU-R BT.709).
+            let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            return if luminance > 0.5 {
+                Background::Light
+            } else {
+                Background::Dark
+            };
+        }
+
+        // Colours 7 (light grey) and 9 to 15 (bright colours) are light; the
+        // rest, including 8 (dark grey), are dark.
+        let colorfgbg = env::var("COLORFGBG").unwrap_or_default();
+        match colorfgbg.rsplit(';').next().map(str::parse::<u8>) {
+            Some(Ok(7 | 9..=15)) => Background::Light,
+            _ => Background::Dark,
+        }
+    }
+}
+
+impl Theme {
+    /// Creates a theme that draws in colour.
+    pub fn color() -> Self {
+        Theme {
+            monochrome: false,
+            palette: Palette::Default,
+            background: Background::Dark,
+        }
+    }
+
+    /// Creates a theme that draws without colour, using only text attributes.
+    pub fn monochrome() -> Self {
+        Theme {
+            monochrome: true,
+            palette: Palette::Default,
+            background: Background::Dark,
+        }
+    }
+
+    /// Returns a copy of this theme that draws colours from `palette`.
+    pub fn with_palette(self, palette: Palette) -> Self {
+        Theme { palette, ..self }
+    }
+
+    /// Returns a copy of this theme with colours tuned for `background`.
+    pub fn with_background(self, background: Background) -> Self {
+        Theme { background, ..self }
+    }
+
+    /// Returns the palette colours are drawn from.
+    pub fn palette(&self) -> Palette {
+        self.palette
+    }
+
+    /// Returns the background colours are tuned for.
+    pub fn background(&self) -> Background {
+        self.background
+    }
+
+    /// Returns `true` if colours are not drawn and renderers must distinguish
+    /// things by other means.
+    pub fn is_monochrome(&self) -> bool {
+        self.monochrome
+    }
+
+    /// Returns the style for a glyph of the given colour and intensity.
+    ///
+    /// In monochrome mode the colour is discarded and only the intensity is
+    /// kept (as the bold and dim attributes). This suits colours that are
+    /// purely decorative.
+    pub fn style(&self, color: usize, intensity: Intensity) -> ContentStyle {
+        let color = color % STYLES.len();
+
+        if self.monochrome {
+            return ContentStyle {
+                attributes: intensity_attributes(intensity),
+                ..ContentStyle::default()
+            };
+        }
+
+        let Some(colors) = self.palette.colors(self.background) else {
+            return match intensity {
+                Intensity::Bold => BOLD_STYLES[color],
+                Intensity::Normal => STYLES[color],
+                Intensity::Dim => DIM_STYLES[color],
+            };
+        };
+
+        let (value, attributes) = match intensity {
+            Intensity::Bold => (colors[color][0], intensity_attributes(intensity)),
+            Intensity::Normal => (colors[color][1], intensity_attributes(intensity)),
+            // High contrast avoids the dim attribute, which many terminals
+            // render at very low contrast. Light backgrounds avoid it too, as
+            // their dim colours are already lightened towards the background.
+            Intensity::Dim
+                if self.palette == Palette::HighContrast
+                    || self.background == Background::Light =>
+            {
+                (colors[color][2], Attributes::none())
+            }
+            Intensity::Dim => (colors[color][2], intensity_attributes(intensity)),
+        };
+
+        ContentStyle {
+            foreground_color: Some(Color::AnsiValue(value)),
+            attributes,
+            ..ContentStyle::default()
+        }
+    }
+
+    /// Returns the style for a glyph whose colour distinguishes it from others.
+    ///
+    /// This is the same as [`Theme::style`] in colour mode. In monochrome mode
+    /// the colour is mapped to a combination of the reverse and underline
+    /// attributes so that differently coloured glyphs remain distinguishable.
+    /// Only four combinations exist, so colours will repeat if more than four
+    /// are used.
+    ///
+    /// Reversed glyphs are drawn in the background colour, so this is best
+    /// suited to small glyphs rather than solid blocks.
+    pub fn marked_style(&self, color: usize, intensity: Intensity) -> ContentStyle {
+        let mut style = self.style(color, intensity);
+
+        if self.monochrome {
+            style
+                .attributes
+                .extend(MONOCHROME_MARKS[color % MONOCHROME_MARKS.len()]);
+        }
+
+        style
+    }
+
+    /// Chooses a random colour from `range` that does not conflict with any
+    /// of the colours in `avoid` (see [`Palette::conflicts`]).
+    ///
+    /// If every colour in the range conflicts, a colour that is not in `avoid`
+    /// is chosen instead, or, if there is none, any colour in the range.
+    pub fn choose_color<R: Rng>(
+        &self,
+        rand: &mut R,
+        range: Range<usize>,
+        avoid: &[usize],
+    ) -> usize {
+        range
+            .clone()
+            .filter(|&color| !avoid.iter().any(|&a| self.palette.conflicts(color, a)))
+            .choose(rand)
+            .or_else(|| {
+                range
+                    .clone()
+                    .filter(|color| !avoid.contains(color))
+                    .choose(rand)
+            })
+            .unwrap_or_else(|| rand.random_range(range))
+    }
+
+    /// Chooses `count` random colours from `range` such that no two of them
+    /// conflict where possible, and no two are the same unless `count` is
+    /// larger than the range.
+    pub fn choose_colors<R: Rng>(
+        &self,
+        rand: &mut R,
+        range: Range<usize>,
+        count: usize,
+    ) -> Vec<usize> {
+        let mut colors = Vec::with_capacity(count);
+        for _ in 0..count {
+            let color = self.choose_color(rand, range.clone(), &colors);
+            colors.push(color);
+        }
+        colors
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        if no_color() {
+            Theme::monochrome()
+        } else {
+            Theme::color()
+        }
+    }
+}
+
+/// Returns `true` if the `NO_COLOR` environment variable is set to a non-empty
+/// value (see <https://no-color.org>).
+pub fn no_color() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+fn intensity_attributes(intensity: Intensity) -> Attributes {
+    match intensity {
+        Intensity::Bold => Attributes::none().with(Attribute::Bold),
+        Intensity::Normal => Attributes::none(),
+        Intensity::Dim => Attributes::none().with(Attribute::Dim),
+    }
+}
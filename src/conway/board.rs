@@ -2,35 +2,36 @@
 // Licensed under the MIT-0 license.
 
 use std::{
+    borrow::Cow,
     hash::{DefaultHasher, Hasher},
-    io::{
-        BufRead, BufReader, Error as IoError, ErrorKind as IoErrorKind, Read, Result as IoResult,
-    },
+    mem, thread,
 };
 
-use rand::{
-    Rng,
-    distr::{
-        Bernoulli, Distribution,
-        uniform::{UniformSampler, UniformUsize},
-    },
+use crate::{
+    census::Census,
+    cycle::{Cycle, CycleDetector},
+    engine::{Engine, Viewport},
+    pattern::Pattern,
+    rule::Rule,
+    topology::Topology,
 };
 
-const HISTORY_LEN: usize = 16;
-
-/// Represents the state of a Conway's Game of Life board.
+/// Represents the state of a Life-like cellular automaton board.
+///
+/// The board is simulated using a [`Rule`], by default Conway's Game of Life
+/// (`B3/S23`). In addition to the usual rules, this implementation includes
+/// coloured cells, which modify the rules as follows:
 ///
-/// In addition to the usual rules of Conway's Game of Life, this implementation
-/// includes coloured cells, which modify the rules as follows:
+/// 1. Any live cell survives if its number of live neighbours **of the same
+///    colour** is one of the rule's survival counts, and otherwise dies.
+/// 2. Any live cell with more live neighbours **of any colour** than the
+///    rule's largest survival count dies, as if by overpopulation.
+/// 3. Any dead cell whose number of live neighbours is one of the rule's birth
+///    counts becomes a live cell, if those neighbours are all **of the same
+///    colour**.
 ///
-/// 1. Any live cell with fewer than two live neighbours **of the same colour**
-///    dies, as if by underpopulation.
-/// 2. Any live cell with two or three live neighbours **of the same colour**
-///    survives.
-/// 3. Any live cell with more than three live neighbours **of any colour** dies,
-///    as if by overpopulation.
-/// 4. Any dead cell with exactly three live neighbours **of the same colour**
-///    becomes a live cell, as if by reproduction.
+/// Under Generations rules, dying cells are kept from being born again while in
+/// one of the rule's refractory states, and become empty afterwards.
 ///
 /// The "colour" values are arbitrary numbers; it is up to renderers to decide
 /// how to display them.
@@ -40,11 +41,17 @@ pub struct Board {
     height: usize,
     cell_buffers: [Vec<Cell>; 2],
     generation: usize,
-    history: [u64; HISTORY_LEN],
+    cycles: CycleDetector<Vec<u64>>,
+    census: Census,
+    rule: Rule,
+    topology: Topology,
 }
 
+/// Smallest number of cells worth stepping on a thread of its own.
+const MIN_CELLS_PER_THREAD: usize = 16 * 1024;
+
 /// Maximum age a cell can reach before becoming empty.
-const MAX_AGE: u32 = 1024;
+pub const MAX_AGE: u32 = 1024;
 
 /// Represents a single cell on the board.
 ///
@@ -55,7 +62,7 @@ const MAX_AGE: u32 = 1024;
 /// With respect to the Game of Life rules, there is no distinction between dead
 /// cells of different ages and empty cells; however, the age is may be used by
 /// renderers to display visual effects such as fading.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct Cell {
     /// The color of the cell, or `None` if the cell is empty.
     ///
@@ -82,86 +89,113 @@ impl Board {
                 vec![Cell::empty(); width * height],
             ],
             generation: 0,
-            history: [0; HISTORY_LEN],
+            cycles: CycleDetector::default(),
+            census: Census::default(),
+            rule: Rule::default(),
+            topology: Topology::default(),
         }
     }
 
-    pub fn with_random_cells<R: Rng>(mut self, rand: &mut R, density: f64) -> Self {
-        let spawn = Bernoulli::new(density).unwrap();
-        let colors = [rand.random_range(1..7), rand.random_range(1..7)];
-        let color_index = UniformUsize::new(0, 2).unwrap();
+    /// Returns a copy of this board that is simulated using the given rule.
+    pub fn with_rule(self, rule: Rule) -> Self {
+        Board { rule, ..self }
+    }
 
-        for cell in self.cell_buffers[0].iter_mut() {
-            if spawn.sample(rand) {
-                let color = colors[color_index.sample(rand)] as u32;
-                *cell = Cell::new(color);
-            }
+    /// Returns a copy of this board whose edges are joined according to the
+    /// given topology.
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Board { topology, ..self }
+    }
+
+    /// Returns a copy of this board that detects cycles with periods up to
+    /// `max_period` (see [`Board::cycle`]).
+    pub fn with_max_period(self, max_period: usize) -> Self {
+        Board {
+            cycles: CycleDetector::new(max_period),
+            ..self
         }
-        self
     }
 
-    /// Updates the board's cells by reading from the given file reader.
-    ///
-    /// The reader should provide a plain text representation of the board,
-    /// with rows of cells where white spaces represent  empty cells and any
-    /// other alphanumeric character represents a living cell. The color of a
-    /// living cell is determined by converting the character to a base-36
-    /// digit.
-    ///
-    /// If the number of rows or columns in the input does not match the board
-    /// size, excess cells will be discarded or missing cells will be treated as
-    /// empty.
-    ///
-    /// Arguments
-    /// =========
-    ///
-    /// - `reader` - A reader providing the board's plain text representation.
+    /// Returns a copy of this board that counts generations from the given
+    /// one, for continuing a simulation started by another engine. This must
+    /// be called after [`Board::with_pattern`], which starts again from 0.
+    pub fn with_generation(mut self, generation: usize) -> Self {
+        // The current cells are kept in the buffer chosen by the generation's
+        // parity.
+        if generation % 2 != self.generation % 2 {
+            self.cell_buffers.swap(0, 1);
+        }
+        Board { generation, ..self }
+    }
+
+    /// Returns a copy of this board with the given census, for continuing a
+    /// simulation started by another engine, so that the cells born and died
+    /// in its last generation are still counted. This must be called after
+    /// [`Board::with_pattern`], which only counts the living and dead cells.
+    pub fn with_census(self, census: Census) -> Self {
+        Board { census, ..self }
+    }
+
+    /// Replaces the board's cells with the given pattern, centred on the
+    /// board.
     ///
-    /// Returns
-    /// =======
+    /// If the pattern is larger than the board, its edges are discarded so
+    /// that its centre remains visible. The pattern's rule is not applied; use
+    /// [`Board::with_rule`] for that, before calling this function if the
+    /// rule's neighbourhood is hexagonal.
+    pub fn with_pattern(self, pattern: &Pattern) -> Self {
+        let (width, height) = pattern.size();
+        let x = (self.width as isize - width as isize) / 2;
+        let y = (self.height as isize - height as isize) / 2;
+        self.with_pattern_at(pattern, x, y)
+    }
+
+    /// Replaces the board's cells with the given pattern, with its top left
+    /// corner at the given coordinates, which may be negative.
     ///
-    /// `Ok(Board)` if the board was successfully loaded, or a
-    /// [`std::io::Error`] if any problems occurred during reading or parsing.
-    pub fn with_cells_from_file<R: Read>(mut self, reader: R) -> IoResult<Self> {
-        let mut reader = BufReader::new(reader);
-        let mut line = String::new();
-
-        for y in 0..self.height {
-            line.clear();
-            if reader.read_line(&mut line)? == 0 {
-                for x in 0..self.width {
-                    self.cell_buffers[0][y + (x * self.width)] = Cell::empty();
-                }
-                continue;
-            }
+    /// Cells that fall outside the board are discarded. On hexagonal boards,
+    /// `y` is rounded down to an even row, as moving a pattern by an odd
+    /// number of rows would change which of its rows are offset, and so its
+    /// shape.
+    pub fn with_pattern_at(
+        mut self,
+        pattern: &Pattern,
+        offset_x: isize,
+        mut offset_y: isize,
+    ) -> Self {
+        if self.rule.neighborhood().is_hexagonal() {
+            offset_y -= offset_y.rem_euclid(2);
+        }
 
-            let mut chars = line.chars();
-            for x in 0..self.width {
-                let ch = chars.next();
-
-                match ch {
-                    Some(ch) if ch.is_whitespace() => {
-                        self.cell_buffers[0][y + (x * self.width)] = Cell::empty();
-                    }
-                    Some(ch) if ch.is_alphanumeric() => {
-                        let color = ch.to_digit(36).unwrap();
-                        self.cell_buffers[0][y + (x * self.width)] = Cell::new(color);
-                    }
-                    Some(other) => {
-                        return Err(IoError::new(
-                            IoErrorKind::InvalidData,
-                            format!("Invalid character '{other}'"),
-                        ));
-                    }
-                    None => self.cell_buffers[0][y + (x * self.width)] = Cell::empty(),
-                }
+        self.cell_buffers[0].fill(Cell::empty());
+        self.cell_buffers[1].fill(Cell::empty());
+        self.generation = 0;
+        self.cycles = CycleDetector::new(self.cycles.max_period());
+
+        for (x, y, cell) in pattern.cells() {
+            let x = x as isize + offset_x;
+            let y = y as isize + offset_y;
+
+            if (0..self.width as isize).contains(&x) && (0..self.height as isize).contains(&y) {
+                self.cell_buffers[0][y as usize * self.width + x as usize] = cell;
             }
         }
+        self.census = Census::of(&self.cell_buffers[0]);
 
-        self.generation = 0;
-        self.cell_buffers[1].fill(Cell::empty());
+        self
+    }
 
-        Ok(self)
+    /// Returns the board's current cells, rule and topology as a pattern of
+    /// the same size, so that they can be saved and later restored with
+    /// [`Board::with_pattern`].
+    pub fn to_pattern(&self) -> Pattern {
+        let mut pattern = Pattern::new(self.width, self.height)
+            .with_rule(self.rule.clone())
+            .with_topology(self.topology);
+        for (i, cell) in self.current_buffer().iter().enumerate() {
+            pattern.set(i % self.width, i / self.width, *cell);
+        }
+        pattern
     }
 
     /// Returns the dimensions of the board as (width, height).
@@ -177,6 +211,35 @@ impl Board {
         &self.current_buffer()[i]
     }
 
+    /// Replaces the cell at the given coordinates, which wrap around the
+    /// board's edges.
+    ///
+    /// The board's census is updated, and cycle detection starts again, as
+    /// the board no longer follows from its earlier generations.
+    pub fn set_cell(&mut self, x: usize, y: usize, cell: Cell) {
+        let i = (y % self.height) * self.width + x % self.width;
+        let current = self.generation % 2;
+
+        let old = mem::replace(&mut self.cell_buffers[current][i], cell);
+        self.census.replace(&old, &cell);
+        self.cycles = CycleDetector::new(self.cycles.max_period());
+    }
+
+    /// Returns the rule the board is simulated with.
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// Returns the longest period of cycle that is detected.
+    pub fn max_period(&self) -> usize {
+        self.cycles.max_period()
+    }
+
+    /// Returns how the board's edges are joined.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
     /// Returns the current generation number (the number of times
     /// [`Board::next`] has been called).
     pub fn generation(&self) -> usize {
@@ -184,70 +247,191 @@ impl Board {
     }
 
     /// Advances the board to the next generation by one simulation step.
+    ///
+    /// The neighbours of each cell are those in the rule's
+    /// [`crate::neighborhood::Neighborhood`]. Neighbours beyond the edges of
+    /// the board are found according to its [`Topology`], or are dead if the
+    /// edge is not joined to another.
+    ///
+    /// Large boards are split into bands of rows that are stepped in parallel,
+    /// one per available CPU, with the same result as stepping them in turn.
     pub fn next(&mut self) {
-        let mut neighbors = Vec::with_capacity(8);
-        let mut board_hasher = DefaultHasher::new();
-
-        for y in 0..self.height {
-            for x in 0..self.width {
-                board_hasher.write_u8(if self.cell(x, y).is_alive() { 1 } else { 0 });
-                neighbors.clear();
-
-                for dy in -1..=1 {
-                    for dx in -1..=1 {
-                        if dx == 0 && dy == 0 {
-                            continue;
-                        }
-
-                        neighbors.push(*self.cell(
-                            ((x as isize + dx + self.width as isize) % self.width as isize)
-                                as usize,
-                            ((y as isize + dy + self.height as isize) % self.height as isize)
-                                as usize,
-                        ));
-                    }
+        let threads = thread::available_parallelism().map_or(1, usize::from);
+        self.next_in_bands((self.width * self.height / MIN_CELLS_PER_THREAD).clamp(1, threads));
+    }
+
+    /// Advances the board to the next generation, stepping the given number
+    /// of bands of rows in parallel (see [`Board::next`]).
+    fn next_in_bands(&mut self, threads: usize) {
+        let [even, odd] = &mut self.cell_buffers;
+        let (current, next) = if self.generation.is_multiple_of(2) {
+            (&*even, odd)
+        } else {
+            (&*odd, even)
+        };
+
+        let state = || current.iter().map(|cell| cell.key(&self.rule)).collect();
+        self.cycles
+            .record(self.generation, hash(current, &self.rule), state);
+
+        let neighborhood = self.rule.neighborhood();
+        let step = Step {
+            current,
+            width: self.width,
+            height: self.height,
+            rule: &self.rule,
+            palette: self.census.colors().map(|(color, _)| color).collect(),
+            topology: self.topology,
+            offsets: [neighborhood.offsets(0), neighborhood.offsets(1)],
+        };
+
+        let band_len = self.height.div_ceil(threads).max(1) * self.width;
+
+        self.census = if threads == 1 {
+            step.band(0, next)
+        } else {
+            thread::scope(|scope| {
+                let bands: Vec<_> = (next.chunks_mut(band_len).enumerate())
+                    .map(|(i, band)| {
+                        let step = &step;
+                        scope.spawn(move || step.band(i * band_len, band))
+                    })
+                    .collect();
+
+                let mut census = Census::default();
+                for band in bands {
+                    census.merge(&band.join().unwrap());
                 }
+                census
+            })
+        };
+
+        self.generation += 1;
+    }
+
+    /// Returns the number of cells of each colour that are alive, and that
+    /// were born or died in the last generation.
+    pub fn census(&self) -> &Census {
+        &self.census
+    }
+
+    /// Returns the cycle of states the board has entered, if any.
+    ///
+    /// Cycles are detected by comparing the states of generations up to the
+    /// maximum period apart (see [`Board::with_max_period`]), including the
+    /// colours of living cells and the ages of refractory cells. Only cycles
+    /// that have repeated exactly are reported.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycles.cycle()
+    }
+
+    fn current_buffer(&self) -> &Vec<Cell> {
+        &self.cell_buffers[self.generation % 2]
+    }
+}
+
+/// Returns the hash of a board's cells that is recorded for cycle detection.
+fn hash(cells: &[Cell], rule: &Rule) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for cell in cells {
+        hasher.write_u64(cell.key(rule));
+    }
+    hasher.finish()
+}
 
-                let i = y * self.width + x;
-                self.next_buffer()[i] = self.current_buffer()[i].next(&neighbors);
+/// The state shared by the threads stepping a board (see [`Board::next`]).
+struct Step<'a> {
+    current: &'a [Cell],
+    width: usize,
+    height: usize,
+    rule: &'a Rule,
+    topology: Topology,
+
+    /// The colours of the living cells on the board (see [`Cell::next`]).
+    palette: Vec<u32>,
+
+    /// The neighbourhood's offsets for even and odd rows.
+    offsets: [Vec<(isize, isize)>; 2],
+}
+
+impl Step<'_> {
+    /// Computes the next state of a band of cells.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `start` - The index of the band's first cell in the board.
+    /// - `band` - The band's cells in the next buffer, to be overwritten.
+    ///
+    /// Returns
+    /// =======
+    ///
+    /// The census of the band's cells.
+    fn band(&self, start: usize, band: &mut [Cell]) -> Census {
+        let mut neighbors = Vec::with_capacity(self.offsets[0].len());
+        let mut census = Census::default();
+
+        for (i, next) in (start..).zip(band.iter_mut()) {
+            let (x, y) = (i % self.width, i / self.width);
+            neighbors.clear();
+
+            for &(dx, dy) in &self.offsets[y % 2] {
+                let (x, y) = (x as isize + dx, y as isize + dy);
+                if let Some((x, y)) = self.topology.locate(x, y, self.width, self.height) {
+                    neighbors.push(self.current[y * self.width + x]);
+                }
             }
+
+            *next = self.current[i].next(&neighbors, self.rule, &self.palette);
+            census.count(&self.current[i], next);
         }
 
-        self.history[self.generation % HISTORY_LEN] = board_hasher.finish();
-        self.generation += 1;
+        census
     }
+}
 
-    /// Returns `true` if the board has converged to a stable or oscillating
-    /// state.
-    ///
-    /// A board is considered converged if it has repeated the same state
-    /// for the past [`HISTORY_LEN`] generations, or if it has alternated
-    /// between two states for the past [`HISTORY_LEN`] generations.
-    pub fn converged(&self) -> bool {
-        if self.generation < HISTORY_LEN {
-            return false;
+impl Engine for Board {
+    fn next(&mut self) {
+        Board::next(self);
+    }
+
+    fn generation(&self) -> usize {
+        Board::generation(self)
+    }
+
+    fn census(&self) -> Census {
+        self.census.clone()
+    }
+
+    fn cycle(&self) -> Option<Cycle> {
+        Board::cycle(self)
+    }
+
+    fn to_pattern(&self) -> Pattern {
+        Board::to_pattern(self)
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, cell: Cell) {
+        if (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y) {
+            Board::set_cell(self, x as usize, y as usize, cell);
         }
+    }
 
-        let count0 = self
-            .history
-            .iter()
-            .filter(|&&h| h == self.history[0])
-            .count();
-        let count1 = self
-            .history
-            .iter()
-            .filter(|&&h| h == self.history[1])
-            .count();
+    fn into_editable(self: Box<Self>) -> Box<dyn Engine> {
+        self
+    }
 
-        count0 == HISTORY_LEN || (count0 == HISTORY_LEN / 2 && count1 == HISTORY_LEN / 2)
+    fn snapshot(&self) -> Box<dyn Engine> {
+        Box::new(self.clone())
     }
 
-    fn current_buffer(&self) -> &Vec<Cell> {
-        &self.cell_buffers[self.generation % 2]
+    /// The board always fills the terminal, so the viewport is ignored.
+    fn view(&self, _viewport: &Viewport) -> Cow<'_, Board> {
+        Cow::Borrowed(self)
     }
 
-    fn next_buffer(&mut self) -> &mut Vec<Cell> {
-        &mut self.cell_buffers[(self.generation + 1) % 2]
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        None
     }
 }
 
@@ -273,6 +457,17 @@ impl Cell {
         self.color.is_some() && self.age == 0
     }
 
+    /// Returns the part of the cell's state that affects the simulation, for
+    /// detecting cycles: its colour if it is alive, its age if it is in a
+    /// refractory state of a Generations rule, or 0 otherwise.
+    pub fn key(&self, rule: &Rule) -> u64 {
+        match self.color {
+            Some(color) if self.is_alive() => 1 << 32 | color as u64,
+            Some(_) if rule.refractory(self.age) => 2 << 32 | self.age as u64,
+            _ => 0,
+        }
+    }
+
     /// Returns `true` if the cell is empty or `false` if it is or was ever
     /// alive.
     pub fn is_empty(&self) -> bool {
@@ -283,35 +478,50 @@ impl Cell {
     ///
     /// The cell's next state is determined by these rules:
     ///
-    /// 1. Any live cell with fewer than two live neighbours of the same color
-    ///    dies, as if by underpopulation.
-    /// 2. Any live cell with two or three live neighbours of the same color
-    ///    survives.
-    /// 3. Any live cell with more than three live neighbours of any color dies,
-    ///    as if by overpopulation.
-    /// 4. Any dead cell with exactly three live neighbours of the same color
-    ///    becomes a live cell, as if by reproduction.
+    /// 1. Any live cell survives if its number of live neighbours of the same
+    ///    color is a survival count of `rule`, and otherwise dies.
+    /// 2. Any live cell with more live neighbours of any color than the largest
+    ///    survival count of `rule` dies, as if by overpopulation.
+    /// 3. Any dead cell whose number of live neighbours is a birth count of
+    ///    `rule` becomes a live cell, if those neighbours all share a color.
+    ///
+    /// Under the rule's other [`crate::inheritance::Inheritance`] policies,
+    /// survival counts live neighbours of every color, and a cell is born
+    /// whatever the colors of its neighbours, taking a color chosen from
+    /// theirs.
+    ///
+    /// A cell in one of the refractory states of a Generations rule is not
+    /// counted as a living neighbour and cannot be born, and becomes empty once
+    /// it has passed through them.
     ///
     /// Arguments
     /// =========
     ///
-    /// - `neighbors` - A slice of neighboring cells. This should contain
-    ///   exactly eight cells that are orthogonally and diagonally adjacent to
-    ///   this cell.
+    /// - `neighbors` - A slice of neighboring cells. This should contain the
+    ///   cells in the rule's neighbourhood, such as the eight cells that are
+    ///   orthogonally and diagonally adjacent to this cell.
+    /// - `rule` - The rule giving the neighbour counts for birth and survival.
+    /// - `palette` - The colors of the living cells on the board, in order,
+    ///   for policies that may give a newborn cell a color none of its
+    ///   neighbours has (see [`crate::inheritance::Inheritance::color`]).
     ///
     /// Returns
     /// =======
     ///
     /// A new [`Cell`] representing the next state.
-    pub fn next(&self, neighbors: &[Cell]) -> Self {
+    pub fn next(&self, neighbors: &[Cell], rule: &Rule, palette: &[u32]) -> Self {
         let living_neighbors = neighbors.iter().filter(|c| c.is_alive()).count();
-        let like_neighbors = neighbors
-            .iter()
-            .filter(|c| c.color == self.color && c.is_alive())
-            .count();
 
         if self.is_alive() {
-            if like_neighbors < 2 || like_neighbors > 3 {
+            let like_neighbors = match rule.inheritance().is_segregated() {
+                true => neighbors
+                    .iter()
+                    .filter(|c| c.color == self.color && c.is_alive())
+                    .count(),
+                false => living_neighbors,
+            };
+
+            if !rule.survives(like_neighbors) || rule.overpopulated(living_neighbors) {
                 // Cell dies and begins aging
                 Cell {
                     color: self.color,
@@ -319,31 +529,27 @@ impl Cell {
                 }
             } else {
                 // Cell survives
-                self.clone()
+                *self
             }
         } else {
-            // Determine if all living neighbors share the same color
-            let neighbor_color = {
-                let mut alive = neighbors.iter().filter(|c| c.is_alive());
-                let mut color = match alive.next() {
-                    Some(cell) => cell.color,
-                    None => None,
-                };
-                for cell in alive {
-                    if cell.color != color {
-                        color = None;
-                        break;
-                    }
-                }
-                color
+            let refractory = !self.is_empty() && rule.refractory(self.age);
+
+            // Choose the color of the newborn cell, if its neighbours agree on
+            // one
+            let neighbor_color = if rule.born(living_neighbors) && !refractory {
+                let parents = neighbors.iter().filter(|c| c.is_alive());
+                rule.inheritance()
+                    .color(parents.filter_map(|c| c.color), palette)
+            } else {
+                None
             };
 
-            if living_neighbors == 3 && neighbor_color.is_some() {
+            if let Some(color) = neighbor_color {
                 // Cell becomes alive
-                Cell {
-                    color: neighbor_color,
-                    age: 0,
-                }
+                Cell::new(color)
+            } else if rule.states() > 2 && !rule.refractory(self.age + 1) {
+                // Cell has passed through its refractory states
+                Cell::empty()
             } else if self.age < MAX_AGE {
                 // Dead cell ages
                 Cell {
@@ -363,3 +569,188 @@ impl Default for Cell {
         Cell::empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::soup::Soup;
+
+    /// Returns a 3 by 2 pattern whose cells each have a different colour, so
+    /// that any transposition or reflection can be seen.
+    fn numbered_pattern() -> Pattern {
+        let mut pattern = Pattern::new(3, 2);
+        for y in 0..2 {
+            for x in 0..3 {
+                pattern.set(x, y, Cell::new((y * 3 + x + 1) as u32));
+            }
+        }
+        pattern
+    }
+
+    /// Returns the positions and colours of the board's living cells, in
+    /// row-major order.
+    fn living_cells(board: &Board) -> Vec<(usize, usize, u32)> {
+        let (width, height) = board.size();
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter_map(|(x, y)| {
+                let cell = board.cell(x, y);
+                cell.is_alive().then(|| (x, y, cell.color.unwrap()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn with_pattern_centres_a_non_square_pattern() {
+        let board = Board::new(7, 4).with_pattern(&numbered_pattern());
+        assert_eq!(
+            living_cells(&board),
+            [
+                (2, 1, 1),
+                (3, 1, 2),
+                (4, 1, 3),
+                (2, 2, 4),
+                (3, 2, 5),
+                (4, 2, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_pattern_at_places_cells_row_major() {
+        let board = Board::new(7, 4).with_pattern_at(&numbered_pattern(), 4, 2);
+        assert_eq!(
+            living_cells(&board),
+            [
+                (4, 2, 1),
+                (5, 2, 2),
+                (6, 2, 3),
+                (4, 3, 4),
+                (5, 3, 5),
+                (6, 3, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_pattern_at_clips_negative_offsets() {
+        let board = Board::new(7, 4).with_pattern_at(&numbered_pattern(), -2, -1);
+        assert_eq!(living_cells(&board), [(0, 0, 6)]);
+        let live: Vec<_> = board
+            .census()
+            .colors()
+            .filter(|(_, counts)| counts.live > 0)
+            .map(|(color, counts)| (color, counts.live))
+            .collect();
+        assert_eq!(live, [(6, 1)]);
+    }
+
+    #[test]
+    fn with_pattern_at_clips_beyond_the_far_edges() {
+        let board = Board::new(7, 4).with_pattern_at(&numbered_pattern(), 5, 3);
+        assert_eq!(living_cells(&board), [(5, 3, 1), (6, 3, 2)]);
+    }
+
+    #[test]
+    fn with_pattern_at_rounds_hexagonal_offsets_to_even_rows() {
+        let rule = "B2/S34H".parse().unwrap();
+        let board = Board::new(7, 6)
+            .with_rule(rule)
+            .with_pattern_at(&numbered_pattern(), 1, 3);
+        assert_eq!(
+            living_cells(&board),
+            [
+                (1, 2, 1),
+                (2, 2, 2),
+                (3, 2, 3),
+                (1, 3, 4),
+                (2, 3, 5),
+                (3, 3, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn set_cell_keeps_the_census_in_step_with_the_cells() {
+        let mut board = Board::new(7, 4).with_pattern(&numbered_pattern());
+        board.set_cell(2, 1, Cell::empty());
+        board.set_cell(0, 0, Cell::new(2));
+        board.set_cell(3, 1, Cell::new(4));
+        board.set_cell(3, 1, Cell::new(4));
+        let live = |census: &Census| {
+            census
+                .colors()
+                .filter(|(_, counts)| counts.live > 0)
+                .map(|(color, counts)| (color, counts.live))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            live(board.census()),
+            live(&Census::of(board.current_buffer()))
+        );
+    }
+
+    /// Returns the colours and ages of every cell in both of the board's
+    /// buffers.
+    fn states(board: &Board) -> Vec<(Option<u32>, u32)> {
+        board
+            .cell_buffers
+            .iter()
+            .flatten()
+            .map(|cell| (cell.color, cell.age))
+            .collect()
+    }
+
+    /// Steps a soup on two copies of a board, one in a single band and one in
+    /// several, and checks that every generation matches exactly.
+    fn assert_bands_match(rule: &str, topology: Topology) {
+        let soup = Soup::new(37, 29).generate(&mut StdRng::seed_from_u64(40), &[1, 2, 3]);
+        let board = Board::new(37, 29)
+            .with_rule(rule.parse().unwrap())
+            .with_topology(topology)
+            .with_pattern(&soup);
+        let mut serial = board.clone();
+        let mut banded = board;
+
+        for _ in 0..64 {
+            serial.next_in_bands(1);
+            banded.next_in_bands(5);
+            assert_eq!(states(&serial), states(&banded));
+            assert_eq!(serial.census, banded.census);
+            assert_eq!(
+                hash(serial.current_buffer(), &serial.rule),
+                hash(banded.current_buffer(), &banded.rule)
+            );
+        }
+        assert_eq!(serial.cycle(), banded.cycle());
+    }
+
+    #[test]
+    fn bands_match_a_single_band_on_a_bounded_board() {
+        assert_bands_match("B3/S23", Topology::Bounded);
+    }
+
+    #[test]
+    fn bands_match_a_single_band_on_a_torus() {
+        assert_bands_match("B3/S23", Topology::Torus);
+    }
+
+    #[test]
+    fn bands_match_a_single_band_with_a_hexagonal_neighbourhood() {
+        assert_bands_match("B2/S34H", Topology::KleinBottle);
+    }
+
+    #[test]
+    fn bands_match_a_single_band_with_refractory_ages() {
+        assert_bands_match("B2/S345/C4", Topology::Torus);
+    }
+
+    #[test]
+    fn to_pattern_round_trips_through_with_pattern() {
+        let board = Board::new(7, 4).with_pattern_at(&numbered_pattern(), 4, 2);
+        let restored = Board::new(7, 4).with_pattern(&board.to_pattern());
+        assert_eq!(living_cells(&restored), living_cells(&board));
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use std::io::{Result as IoResult, Write};
+
+use crate::board::Cell;
+
+/// Counts of the cells of one colour.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    /// The number of living cells.
+    pub live: usize,
+
+    /// The number of cells born in the last generation.
+    pub born: usize,
+
+    /// The number of cells that died in the last generation.
+    pub died: usize,
+}
+
+/// The number of cells of each colour that are alive, and that were born or
+/// died in the last generation, so that the colours' populations can be
+/// compared.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Census {
+    /// The counts for each colour that has ever been counted, in order of
+    /// colour.
+    counts: Vec<(u32, Counts)>,
+}
+
+impl Census {
+    /// Counts the living cells of each colour among the given cells, with no
+    /// births or deaths.
+    pub fn of<'a>(cells: impl IntoIterator<Item = &'a Cell>) -> Self {
+        let mut census = Census::default();
+        for cell in cells {
+            if let Some(color) = cell.color
+                && cell.is_alive()
+            {
+                census.entry(color).live += 1;
+            }
+        }
+        census
+    }
+
+    /// Counts a cell that has changed from `old` to `new` in the last
+    /// generation.
+    pub fn count(&mut self, old: &Cell, new: &Cell) {
+        if let Some(color) = new.color
+            && new.is_alive()
+        {
+            let counts = self.entry(color);
+            counts.live += 1;
+            counts.born += !old.is_alive() as usize;
+        } else if let Some(color) = old.color
+            && old.is_alive()
+        {
+            self.entry(color).died += 1;
+        }
+    }
+
+    /// Counts a cell that has been replaced by editing rather than by a
+    /// generation, so only the living counts change.
+    pub fn replace(&mut self, old: &Cell, new: &Cell) {
+        if let Some(color) = old.color
+            && old.is_alive()
+        {
+            self.entry(color).live -= 1;
+        }
+        if let Some(color) = new.color
+            && new.is_alive()
+        {
+            self.entry(color).live += 1;
+        }
+    }
+
+    /// Returns the counts for each colour that has been counted, in order of
+    /// colour.
+    pub fn colors(&self) -> impl Iterator<Item = (u32, Counts)> + '_ {
+        self.counts.iter().copied()
+    }
+
+    /// Returns the counts for the given colour, for updating.
+    pub fn entry(&mut self, color: u32) -> &mut Counts {
+        let i = match self
+            .counts
+            .binary_search_by_key(&color, |&(color, _)| color)
+        {
+            Ok(i) => i,
+            Err(i) => {
+                self.counts.insert(i, (color, Counts::default()));
+                i
+            }
+        };
+        &mut self.counts[i].1
+    }
+
+    /// Adds another census's counts to this one's, such as those of another
+    /// part of the board.
+    pub fn merge(&mut self, other: &Census) {
+        for (color, counts) in other.colors() {
+            let entry = self.entry(color);
+            entry.live += counts.live;
+            entry.born += counts.born;
+            entry.died += counts.died;
+        }
+    }
+
+    /// Writes the header of a CSV file of censuses (see
+    /// [`Census::write_csv`]).
+    pub fn write_csv_header<W: Write>(writer: &mut W) -> IoResult<()> {
+        writeln!(writer, "run,generation,color,live,born,died")
+    }
+
+    /// Writes the census as rows of a CSV file, one for each colour.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `writer` - The writer to write to.
+    /// - `run` - The number of times the simulation has been reset.
+    /// - `generation` - The generation the census was taken in.
+    pub fn write_csv<W: Write>(
+        &self,
+        writer: &mut W,
+        run: usize,
+        generation: usize,
+    ) -> IoResult<()> {
+        for (color, counts) in self.colors() {
+            let Counts { live, born, died } = counts;
+            writeln!(writer, "{run},{generation},{color},{live},{born},{died}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use std::{collections::VecDeque, fmt};
+
+/// Default longest period of cycle that is detected.
+pub const DEFAULT_MAX_PERIOD: usize = 64;
+
+/// A cycle of states that a simulation has entered, and will repeat forever.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The number of generations after which the states repeat; 1 for a still
+    /// life.
+    pub period: usize,
+
+    /// The first generation of the cycle.
+    pub start: usize,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.period {
+            1 => write!(f, "still life reached at gen {}", self.start),
+            period => write!(
+                f,
+                "period {period} oscillator reached at gen {}",
+                self.start
+            ),
+        }
+    }
+}
+
+/// Detects when a simulation enters a cycle of states.
+///
+/// The hash of each generation's state is recorded, and a cycle is suspected
+/// when a hash matches one from up to the maximum period earlier. The state
+/// is then kept, and the cycle is only confirmed if it is exactly equal to the
+/// state one period later, so that hash collisions are never reported as
+/// cycles.
+#[derive(Clone, Debug)]
+pub struct CycleDetector<S> {
+    max_period: usize,
+
+    /// The hashes of recent generations, most recent last.
+    hashes: VecDeque<u64>,
+
+    /// The generation of the most recent hash.
+    generation: usize,
+
+    /// A suspected cycle, with the state of the generation at which it was
+    /// suspected.
+    candidate: Option<(Cycle, usize, S)>,
+
+    cycle: Option<Cycle>,
+}
+
+impl<S: PartialEq> CycleDetector<S> {
+    /// Creates a detector for cycles with periods up to `max_period`.
+    pub fn new(max_period: usize) -> Self {
+        CycleDetector {
+            max_period: max_period.max(1),
+            hashes: VecDeque::new(),
+            generation: 0,
+            candidate: None,
+            cycle: None,
+        }
+    }
+
+    /// Returns the longest period of cycle that is detected.
+    pub fn max_period(&self) -> usize {
+        self.max_period
+    }
+
+    /// Returns the cycle the simulation has entered, if it has been detected.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Records the state of a generation, which must be one after the last
+    /// recorded.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `generation` - The generation number.
+    /// - `hash` - A hash of the generation's state.
+    /// - `state` - Returns the generation's state, which is only called if it
+    ///   needs to be compared or kept.
+    pub fn record(&mut self, generation: usize, hash: u64, state: impl FnOnce() -> S) {
+        if self.cycle.is_some() {
+            return;
+        }
+
+        if !self.hashes.is_empty() && generation != self.generation + 1 {
+            self.hashes.clear();
+            self.candidate = None;
+        }
+        self.generation = generation;
+
+        // The state is computed at most once, and kept if a suspected cycle
+        // turns out to be a hash collision.
+        let mut state = Some(state);
+        let mut current = None;
+
+        if let Some((cycle, suspected, _)) = &self.candidate
+            && generation == suspected + cycle.period
+        {
+            let (cycle, _, expected) = self.candidate.take().unwrap();
+            let actual = state.take().unwrap()();
+            if actual == expected {
+                self.cycle = Some(cycle);
+                return;
+            }
+            current = Some(actual);
+        }
+
+        if self.candidate.is_none()
+            && let Some(period) = (1..=self.max_period.min(self.hashes.len()))
+                .find(|&period| self.hashes[self.hashes.len() - period] == hash)
+        {
+            // Find where the cycle started, as far back as the hashes go.
+            let repeats = (period..self.hashes.len())
+                .rev()
+                .take_while(|&i| self.hashes[i] == self.hashes[i - period])
+                .count();
+            let cycle = Cycle {
+                period,
+                start: generation - period - repeats,
+            };
+
+            let current = match current {
+                Some(current) => current,
+                None => state.take().unwrap()(),
+            };
+            self.candidate = Some((cycle, generation, current));
+        }
+
+        self.hashes.push_back(hash);
+        if self.hashes.len() > 2 * self.max_period {
+            self.hashes.pop_front();
+        }
+    }
+}
+
+impl<S: PartialEq> Default for CycleDetector<S> {
+    fn default() -> Self {
+        CycleDetector::new(DEFAULT_MAX_PERIOD)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::{Board, Cell},
+        library,
+        pattern::Pattern,
+    };
+
+    /// Steps a board with the given pattern until a cycle is detected, or for
+    /// at most 100 generations.
+    fn detect(pattern: &Pattern) -> Option<Cycle> {
+        let mut board = Board::new(24, 24).with_pattern(pattern);
+        for _ in 0..100 {
+            board.next();
+            if board.cycle().is_some() {
+                break;
+            }
+        }
+        board.cycle()
+    }
+
+    /// Returns a pattern of living cells at the given coordinates.
+    fn pattern(cells: &[(usize, usize)]) -> Pattern {
+        let mut pattern = Pattern::new(0, 0);
+        for &(x, y) in cells {
+            pattern.set(x, y, Cell::new(1));
+        }
+        pattern
+    }
+
+    #[test]
+    fn still_lifes_have_period_1() {
+        let block = pattern(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        assert_eq!(
+            detect(&block),
+            Some(Cycle {
+                period: 1,
+                start: 0
+            })
+        );
+
+        // Three cells of a block become the block in one generation.
+        let corner = pattern(&[(0, 0), (1, 0), (0, 1)]);
+        assert_eq!(
+            detect(&corner),
+            Some(Cycle {
+                period: 1,
+                start: 1
+            })
+        );
+    }
+
+    #[test]
+    fn blinker_has_period_2() {
+        let blinker = pattern(&[(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(
+            detect(&blinker),
+            Some(Cycle {
+                period: 2,
+                start: 0
+            })
+        );
+    }
+
+    #[test]
+    fn pentadecathlon_has_period_15() {
+        let index = library::PATTERNS
+            .iter()
+            .position(|(name, _)| *name == "Pentadecathlon");
+        let pentadecathlon = library::get(index.unwrap());
+        assert_eq!(
+            detect(&pentadecathlon),
+            Some(Cycle {
+                period: 15,
+                start: 0
+            })
+        );
+    }
+
+    #[test]
+    fn finds_the_start_of_a_cycle_after_a_transient() {
+        let mut detector = CycleDetector::new(8);
+        for (generation, hash) in [10, 11, 12, 1, 2, 3, 1, 2, 3, 1].into_iter().enumerate() {
+            detector.record(generation, hash, || hash);
+        }
+        assert_eq!(
+            detector.cycle(),
+            Some(Cycle {
+                period: 3,
+                start: 3
+            })
+        );
+    }
+
+    #[test]
+    fn hash_collisions_are_not_cycles() {
+        let mut detector = CycleDetector::new(8);
+        for generation in 0..100 {
+            detector.record(generation, 7, || generation);
+        }
+        assert_eq!(detector.cycle(), None);
+    }
+
+    #[test]
+    fn periods_longer_than_the_maximum_are_not_detected() {
+        let mut detector = CycleDetector::new(2);
+        for generation in 0..100 {
+            detector.record(generation, generation as u64 % 3, || generation % 3);
+        }
+        assert_eq!(detector.cycle(), None);
+    }
+
+    #[test]
+    fn skipped_generations_start_detection_again() {
+        let mut detector = CycleDetector::new(8);
+        detector.record(0, 1, || 1);
+        detector.record(5, 1, || 1);
+        assert_eq!(detector.cycle(), None);
+        detector.record(6, 1, || 1);
+        detector.record(7, 1, || 1);
+        assert_eq!(
+            detector.cycle(),
+            Some(Cycle {
+                period: 1,
+                start: 5
+            })
+        );
+    }
+}
@@ -0,0 +1,311 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use std::io::Result as IoResult;
+
+use crossterm::event::KeyCode;
+use rand::distr::{Bernoulli, Distribution};
+
+use crate::{
+    board::Cell,
+    engine::{Engine, Viewport},
+    library::{self, PATTERNS},
+    pattern::Pattern,
+    renderer::{render_cursor, render_message},
+};
+
+/// Keys handled by the editor, in addition to those that exit.
+pub const KEYS: [KeyCode; 23] = [
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Char(' '),
+    KeyCode::Char('1'),
+    KeyCode::Char('2'),
+    KeyCode::Char('3'),
+    KeyCode::Char('4'),
+    KeyCode::Char('5'),
+    KeyCode::Char('6'),
+    KeyCode::Char('7'),
+    KeyCode::Char('8'),
+    KeyCode::Char('9'),
+    KeyCode::Char('v'),
+    KeyCode::Char('x'),
+    KeyCode::Char('r'),
+    KeyCode::Char('e'),
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Char('o'),
+    KeyCode::Char('m'),
+    KeyCode::Char('p'),
+];
+
+/// Probability that each cell of a randomised region is alive.
+const RANDOM_DENSITY: f64 = 0.33;
+
+/// The state of the pattern editor, in which the simulation is paused so that
+/// cells can be drawn.
+///
+/// The arrow keys move a cursor, space toggles the cell under it, and the digit
+/// keys choose the colour of the cells drawn. Pressing 'v' marks one corner of
+/// a region, whose opposite corner is the cursor, and 'x' or 'r' clears or
+/// randomises it, or the whole view if no region is marked. Tab picks one of
+/// the built-in patterns (see [`library::PATTERNS`]), 'o' and 'm' rotate and
+/// mirror it, and 'p' stamps it centred on the cursor. 'e' or Enter resumes
+/// the simulation.
+pub struct Editor {
+    cursor: (i64, i64),
+    mark: Option<(i64, i64)>,
+    color: u32,
+    hexagonal: bool,
+
+    /// The index of the built-in pattern picked for stamping, and the
+    /// pattern as rotated and mirrored.
+    stamp: Option<(usize, Pattern)>,
+}
+
+impl Editor {
+    /// Creates an editor with its cursor in the centre of the viewport.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `viewport` - The part of the universe shown.
+    /// - `color` - The colour of the cells drawn.
+    /// - `hexagonal` - Whether the board is drawn with offset rows.
+    pub fn new(viewport: &Viewport, color: u32, hexagonal: bool) -> Self {
+        let (x, y) = viewport.origin(hexagonal);
+        Editor {
+            cursor: (
+                x + viewport.width as i64 / 2,
+                y + viewport.height as i64 / 2,
+            ),
+            mark: None,
+            color,
+            hexagonal,
+            stamp: None,
+        }
+    }
+
+    /// Returns the colour of the cells drawn.
+    pub fn color(&self) -> u32 {
+        self.color
+    }
+
+    /// Handles a key press.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `key` - One of the editor's [`KEYS`].
+    /// - `engine` - The engine whose cells are edited, which should be
+    ///   editable (see [`Engine::into_editable`]).
+    /// - `viewport` - The part of the universe shown, which is panned to keep
+    ///   the cursor in view.
+    /// - `bounded` - Whether the universe is a board the size of the viewport,
+    ///   in which case the cursor stays within it instead.
+    ///
+    /// Returns
+    /// =======
+    ///
+    /// `true` if the user asked to resume the simulation.
+    pub fn handle(
+        &mut self,
+        key: KeyCode,
+        engine: &mut dyn Engine,
+        viewport: &mut Viewport,
+        bounded: bool,
+    ) -> bool {
+        match key {
+            KeyCode::Left => self.move_cursor(-1, 0, viewport, bounded),
+            KeyCode::Right => self.move_cursor(1, 0, viewport, bounded),
+            KeyCode::Up => self.move_cursor(0, -1, viewport, bounded),
+            KeyCode::Down => self.move_cursor(0, 1, viewport, bounded),
+            KeyCode::Char(' ') => {
+                let (x, y) = self.cursor;
+                let cell = if self.cell(engine, viewport).is_alive() {
+                    Cell::empty()
+                } else {
+                    Cell::new(self.color)
+                };
+                engine.set_cell(x, y, cell);
+            }
+            KeyCode::Char(digit @ '1'..='9') => self.color = digit as u32 - '0' as u32,
+            KeyCode::Char('v') => {
+                self.mark = match self.mark {
+                    Some(_) => None,
+                    None => Some(self.cursor),
+                }
+            }
+            KeyCode::Char('x') => self.fill(engine, viewport, |_| Cell::empty()),
+            KeyCode::Char('r') => {
+                let spawn = Bernoulli::new(RANDOM_DENSITY).unwrap();
+                let mut rand = rand::rng();
+                let color = self.color;
+                self.fill(engine, viewport, |_| match spawn.sample(&mut rand) {
+                    true => Cell::new(color),
+                    false => Cell::empty(),
+                });
+            }
+            KeyCode::Tab => {
+                let next = match &self.stamp {
+                    Some((index, _)) => index + 1,
+                    None => 0,
+                };
+                self.stamp = (next < PATTERNS.len()).then(|| (next, library::get(next)));
+            }
+            KeyCode::Char('o') => {
+                if let Some((index, pattern)) = self.stamp.take() {
+                    self.stamp = Some((index, pattern.rotated()));
+                }
+            }
+            KeyCode::Char('m') => {
+                if let Some((index, pattern)) = self.stamp.take() {
+                    self.stamp = Some((index, pattern.mirrored()));
+                }
+            }
+            KeyCode::Char('p') => {
+                for (x, y, _) in self.stamp_cells() {
+                    engine.set_cell(x, y, Cell::new(self.color));
+                }
+            }
+            KeyCode::Char('e') | KeyCode::Enter => return true,
+            _ => {}
+        }
+
+        false
+    }
+
+    /// Draws the cursor, the marked corner of the region and a status line
+    /// over the board rendered from the viewport.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `viewport` - The part of the universe shown.
+    /// - `columns` - The width of the terminal in columns.
+    /// - `rows` - The height of the terminal in rows.
+    pub fn render(&self, viewport: &Viewport, columns: usize, rows: usize) -> IoResult<()> {
+        let (min_x, min_y, max_x, max_y) = self.visible(viewport);
+        let on_screen = |(x, y): (i64, i64)| {
+            ((min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y))
+                .then_some(((x - min_x) as usize, (y - min_y) as usize))
+        };
+
+        for (x, y) in self.stamp_cells().filter_map(|(x, y, _)| on_screen((x, y))) {
+            render_cursor(x, y, self.hexagonal, '*')?;
+        }
+        if let Some((x, y)) = self.mark.and_then(on_screen) {
+            render_cursor(x, y, self.hexagonal, 'o')?;
+        }
+        if let Some((x, y)) = on_screen(self.cursor) {
+            render_cursor(x, y, self.hexagonal, '+')?;
+        }
+
+        let region = match self.region(viewport) {
+            (min_x, min_y, max_x, max_y) if self.mark.is_some() => {
+                format!("region {}x{}", max_x - min_x + 1, max_y - min_y + 1)
+            }
+            _ => "no region".to_string(),
+        };
+        let stamp = match &self.stamp {
+            Some((index, _)) => PATTERNS[*index].0,
+            None => "no pattern",
+        };
+        let status = format!(
+            "EDIT colour {} {region} {stamp} | arrows move, space toggles, 1-9 colour, v marks, \
+             x clears, r randomises, tab picks, o rotates, m mirrors, p stamps, e resumes",
+            self.color
+        );
+        render_message(&status, columns, rows)
+    }
+
+    /// Moves the cursor, keeping it within the viewport if the universe is
+    /// bounded, or panning the viewport to follow it otherwise.
+    fn move_cursor(&mut self, dx: i64, dy: i64, viewport: &mut Viewport, bounded: bool) {
+        let (x, y) = (self.cursor.0 + dx, self.cursor.1 + dy);
+        let (min_x, min_y, max_x, max_y) = self.visible(viewport);
+
+        if bounded {
+            self.cursor = (x.clamp(min_x, max_x), y.clamp(min_y, max_y));
+            return;
+        }
+
+        // Hexagonal rows are shown in pairs (see `Viewport::origin`), so the
+        // viewport must move two rows at a time to show the next row.
+        let dx = (x - max_x).max(0) + (x - min_x).min(0);
+        let mut dy = (y - max_y).max(0) + (y - min_y).min(0);
+        if self.hexagonal {
+            dy += dy % 2;
+        }
+
+        self.cursor = (x, y);
+        viewport.pan(dx, dy);
+    }
+
+    /// Returns the cell under the cursor, which must be in view.
+    fn cell(&self, engine: &dyn Engine, viewport: &Viewport) -> Cell {
+        let (min_x, min_y, _, _) = self.visible(viewport);
+        *engine.view(viewport).cell(
+            (self.cursor.0 - min_x) as usize,
+            (self.cursor.1 - min_y) as usize,
+        )
+    }
+
+    /// Returns the cells of the picked pattern, centred on the cursor, with
+    /// their coordinates in the universe.
+    fn stamp_cells(&self) -> impl Iterator<Item = (i64, i64, Cell)> + '_ {
+        self.stamp.iter().flat_map(|(_, pattern)| {
+            let (width, height) = pattern.size();
+            let x = self.cursor.0 - width as i64 / 2;
+            let y = self.cursor.1 - height as i64 / 2;
+            pattern
+                .cells()
+                .map(move |(cell_x, cell_y, cell)| (x + cell_x as i64, y + cell_y as i64, cell))
+        })
+    }
+
+    /// Returns the cells shown from the viewport as `(min_x, min_y, max_x,
+    /// max_y)` (see [`Viewport::origin`]).
+    fn visible(&self, viewport: &Viewport) -> (i64, i64, i64, i64) {
+        let (x, y) = viewport.origin(self.hexagonal);
+        (
+            x,
+            y,
+            x + viewport.width as i64 - 1,
+            y + viewport.height as i64 - 1,
+        )
+    }
+
+    /// Returns the marked region as `(min_x, min_y, max_x, max_y)`, or the
+    /// whole viewport if no region is marked.
+    fn region(&self, viewport: &Viewport) -> (i64, i64, i64, i64) {
+        match self.mark {
+            Some((x, y)) => (
+                x.min(self.cursor.0),
+                y.min(self.cursor.1),
+                x.max(self.cursor.0),
+                y.max(self.cursor.1),
+            ),
+            None => self.visible(viewport),
+        }
+    }
+
+    /// Replaces every cell in the marked region, or the whole viewport if no
+    /// region is marked, and clears the mark.
+    fn fill(
+        &mut self,
+        engine: &mut dyn Engine,
+        viewport: &Viewport,
+        mut cell: impl FnMut((i64, i64)) -> Cell,
+    ) {
+        let (min_x, min_y, max_x, max_y) = self.region(viewport);
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                engine.set_cell(x, y, cell((x, y)));
+            }
+        }
+        self.mark = None;
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use std::borrow::Cow;
+
+use crate::{
+    board::{Board, Cell},
+    census::Census,
+    cycle::Cycle,
+    pattern::Pattern,
+};
+
+/// A simulation engine, which advances a universe of cells one generation at
+/// a time.
+///
+/// The dense [`Board`] is the simplest engine, with one cell per character of
+/// the terminal. Other engines may simulate universes larger than the terminal,
+/// in which case a [`Viewport`] selects the part of the universe to show.
+pub trait Engine {
+    /// Advances the universe to the next generation.
+    fn next(&mut self);
+
+    /// Advances the universe by the given number of generations.
+    ///
+    /// By default this advances one generation at a time, but engines such as
+    /// [`HashLife`](crate::hashlife::HashLife) may skip ahead much faster.
+    fn jump(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.next();
+        }
+    }
+
+    /// Returns the current generation number.
+    fn generation(&self) -> usize;
+
+    /// Returns the number of cells of each colour that are alive, and that
+    /// were born or died in the last generation.
+    fn census(&self) -> Census;
+
+    /// Returns the cycle of states the universe has entered, if one has been
+    /// detected.
+    fn cycle(&self) -> Option<Cycle>;
+
+    /// Returns the universe's cells, rule and topology as a pattern, so that
+    /// they can be saved.
+    fn to_pattern(&self) -> Pattern;
+
+    /// Sets the cell at the given coordinates, for editing. Cells outside a
+    /// bounded board are ignored.
+    ///
+    /// Engines that only hold cells of one colour give the cell their own
+    /// colour (see [`Engine::into_editable`]). Cycle detection starts again, as
+    /// the universe no longer follows from its earlier generations.
+    fn set_cell(&mut self, x: i64, y: i64, cell: Cell);
+
+    /// Converts the engine into one that can hold cells of any colour, if it
+    /// is not already, so that it can be edited. Engines that never hold more
+    /// than one colour return themselves.
+    fn into_editable(self: Box<Self>) -> Box<dyn Engine>;
+
+    /// Returns a copy of the engine in its current state, which can later
+    /// take its place to return to this generation (see
+    /// [`Rewind`](crate::rewind::Rewind)).
+    fn snapshot(&self) -> Box<dyn Engine>;
+
+    /// Returns a board showing the part of the universe inside the viewport,
+    /// for rendering.
+    fn view(&self, viewport: &Viewport) -> Cow<'_, Board>;
+
+    /// Returns the bounding box of the living cells as `(min_x, min_y, max_x,
+    /// max_y)`, or `None` if there are none or the universe cannot be panned.
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)>;
+}
+
+/// The part of an unbounded universe that is shown in the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Viewport {
+    /// The x coordinate of the leftmost column shown.
+    pub x: i64,
+
+    /// The y coordinate of the topmost row shown.
+    pub y: i64,
+
+    /// The number of columns of cells shown.
+    pub width: usize,
+
+    /// The number of rows of cells shown.
+    pub height: usize,
+}
+
+impl Viewport {
+    /// Creates a viewport of the given size with its top left corner at the
+    /// origin.
+    pub fn new(width: usize, height: usize) -> Self {
+        Viewport {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }
+    }
+
+    /// Moves the viewport by the given number of cells.
+    pub fn pan(&mut self, dx: i64, dy: i64) {
+        self.x += dx;
+        self.y += dy;
+    }
+
+    /// Returns the coordinates of the cell at the top left of the board
+    /// returned by [`Engine::view`]. Rows of hexagonal boards must keep their
+    /// parity so that they are offset correctly, so the board may start one
+    /// row above the viewport.
+    pub fn origin(&self, hexagonal: bool) -> (i64, i64) {
+        match hexagonal {
+            true => (self.x, self.y - self.y.rem_euclid(2)),
+            false => (self.x, self.y),
+        }
+    }
+
+    /// Moves the viewport so that it is centred on the given bounding box (see
+    /// [`Engine::bounds`]). Does nothing if `bounds` is `None`.
+    pub fn centre_on(&mut self, bounds: Option<(i64, i64, i64, i64)>) {
+        if let Some((min_x, min_y, max_x, max_y)) = bounds {
+            self.x = (min_x + max_x).div_euclid(2) - self.width as i64 / 2;
+            self.y = (min_y + max_y).div_euclid(2) - self.height as i64 / 2;
+        }
+    }
+}
@@ -0,0 +1,316 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+//! Readers and writers for the pattern file formats understood by the
+//! simulator.
+//!
+//! The RLE and plain text formats may also carry the colour and age of dead
+//! cells, which are otherwise lost. Each dead cell is written on a directive
+//! line of the form `#A x y color age`. Other formats ignore these lines.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Error as IoError, ErrorKind as IoErrorKind, Read, Result as IoResult, Write},
+    path::Path,
+};
+
+use crate::{board::Cell, pattern::Pattern, rule::Rule, topology::Topology};
+
+pub mod cells;
+pub mod life;
+pub mod rle;
+pub mod text;
+
+/// A pattern file format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Run length encoded patterns (see [`rle`]).
+    Rle,
+
+    /// LifeWiki plaintext patterns (see [`cells`]).
+    Cells,
+
+    /// Life 1.05 patterns (see [`life`]).
+    Life105,
+
+    /// Life 1.06 patterns (see [`life`]).
+    Life106,
+
+    /// Plain text with base-36 coloured cells (see [`text`]).
+    Text,
+}
+
+impl Format {
+    /// Guesses the format of a file from its extension.
+    ///
+    /// Returns `None` if the extension is missing or ambiguous. Both Life
+    /// formats share the `.lif` and `.life` extensions, so those are
+    /// distinguished by their header instead.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "rle" => Some(Format::Rle),
+            "cells" => Some(Format::Cells),
+            "txt" => Some(Format::Text),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format of a file from its content.
+    ///
+    /// Life files are recognised by their `#Life` header, and RLE files by
+    /// their `x = ...` header line following any `#` comments. Files with `!`
+    /// comments or `.` cells are plaintext; anything else is assumed to be the
+    /// plain text coloured format.
+    pub fn sniff(content: &str) -> Self {
+        let first = content.lines().next().unwrap_or_default().trim();
+        if first.starts_with("#Life 1.05") {
+            return Format::Life105;
+        } else if first.starts_with("#Life 1.06") {
+            return Format::Life106;
+        }
+
+        let header = content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'));
+        if header.is_some_and(|line| line.starts_with('x') && line.contains('=')) {
+            return Format::Rle;
+        }
+
+        let plaintext = content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .any(|line| line.starts_with('!') || line.contains('.'));
+        if plaintext {
+            Format::Cells
+        } else {
+            Format::Text
+        }
+    }
+
+    /// Writes a pattern in this format.
+    ///
+    /// Only the RLE and plain text formats can be written, as the others
+    /// cannot represent colours. If `ages` is `true`, the colours and ages of
+    /// dead cells are written too.
+    pub fn write<W: Write>(self, pattern: &Pattern, writer: W, ages: bool) -> IoResult<()> {
+        match self {
+            Format::Rle => rle::write(pattern, writer, ages),
+            Format::Text => text::write(pattern, writer, ages),
+            other => Err(IoError::new(
+                IoErrorKind::Unsupported,
+                format!("patterns cannot be saved in {other:?} format"),
+            )),
+        }
+    }
+
+    /// Reads a pattern in this format.
+    pub fn read<R: Read>(self, reader: R) -> IoResult<Pattern> {
+        match self {
+            Format::Rle => rle::read(reader),
+            Format::Cells => cells::read(reader),
+            Format::Life105 => life::read_105(reader),
+            Format::Life106 => life::read_106(reader),
+            Format::Text => text::read(reader),
+        }
+    }
+}
+
+/// Reads a pattern in any supported format.
+///
+/// The format is chosen by the extension of `path`, if given, or otherwise
+/// by examining the content (see [`Format::sniff`]).
+pub fn read<R: Read>(path: Option<&Path>, mut reader: R) -> IoResult<Pattern> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+
+    let format = path
+        .and_then(Format::from_path)
+        .unwrap_or_else(|| Format::sniff(&content));
+
+    format.read(content.as_bytes())
+}
+
+/// Saves a pattern to the file at `path`.
+///
+/// The format is chosen by the file's extension, defaulting to RLE if the
+/// extension is not recognised (see [`Format::write`]).
+pub fn save(path: &Path, pattern: &Pattern, ages: bool) -> IoResult<()> {
+    let format = Format::from_path(path).unwrap_or(Format::Rle);
+    let mut writer = BufWriter::new(File::create(path)?);
+    format.write(pattern, &mut writer, ages)?;
+    writer.flush()
+}
+
+/// Parses a `#A x y color age` directive giving the colour and age of a dead
+/// cell.
+fn parse_age(line_number: usize, line: &str) -> IoResult<(usize, usize, Cell)> {
+    let mut words = line.trim_start_matches("#A").split_whitespace();
+    let mut values = [0; 4];
+
+    for (value, name) in values.iter_mut().zip(["x", "y", "color", "age"]) {
+        let Some(word) = words.next() else {
+            return Err(parse_error(
+                line_number,
+                line.chars().count() + 1,
+                format!("expected {name}"),
+            ));
+        };
+
+        *value = word.parse().map_err(|_| {
+            parse_error(
+                line_number,
+                column_of(line, word),
+                format!("'{word}' is not a valid {name}"),
+            )
+        })?;
+    }
+
+    let [x, y, color, age] = values;
+    if age == 0 {
+        return Err(parse_error(
+            line_number,
+            1,
+            "dead cells must have an age of at least 1",
+        ));
+    }
+
+    let cell = Cell {
+        color: Some(color as u32),
+        age: age as u32,
+    };
+    Ok((x, y, cell))
+}
+
+/// Parses a rule, optionally followed by a colon and the topology in Golly's
+/// notation (such as `B3/S23:P80,40`), and applies them to `pattern`. The
+/// pattern is grown to the size of the board the topology describes.
+///
+/// `column` is the column of `value` in its line, used to report errors.
+fn parse_rule(
+    pattern: Pattern,
+    line_number: usize,
+    column: usize,
+    value: &str,
+) -> IoResult<Pattern> {
+    let (rule, topology) = match value.split_once(':') {
+        Some((rule, topology)) => (rule, Some(topology)),
+        None => (value, None),
+    };
+
+    let rule = rule
+        .parse::<Rule>()
+        .map_err(|err| parse_error(line_number, column, err))?;
+    let pattern = pattern.with_rule(rule);
+
+    match topology {
+        Some(topology) => match Topology::from_golly(topology) {
+            Some((topology, width, height)) => {
+                let mut pattern = pattern.with_topology(topology);
+                pattern.grow(width, height);
+                Ok(pattern)
+            }
+            None => Err(parse_error(
+                line_number,
+                column_of(value, topology) + column - 1,
+                format!("unsupported topology '{}'", topology.trim()),
+            )),
+        },
+        None => Ok(pattern),
+    }
+}
+
+/// Formats the rule and topology of a pattern as read by [`parse_rule`].
+///
+/// Returns `None` if the pattern has neither a rule nor a topology.
+fn rule_string(pattern: &Pattern) -> Option<String> {
+    let rule = match (pattern.rule(), pattern.topology()) {
+        (None, None) => return None,
+        (rule, _) => rule.cloned().unwrap_or_default(),
+    };
+
+    Some(match pattern.topology() {
+        Some(topology) => {
+            let (width, height) = pattern.size();
+            format!("{rule}:{}", topology.to_golly(width, height))
+        }
+        None => rule.to_string(),
+    })
+}
+
+/// Writes a `#A x y color age` directive for every dead cell in `pattern`.
+fn write_ages<W: Write>(pattern: &Pattern, mut writer: W) -> IoResult<()> {
+    for (x, y, cell) in pattern.cells() {
+        if let (Some(color), false) = (cell.color, cell.is_alive()) {
+            writeln!(writer, "#A {x} {y} {color} {}", cell.age)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the one-based column at which `word`, a slice of `line`, starts.
+fn column_of(line: &str, word: &str) -> usize {
+    let offset = word.as_ptr() as usize - line.as_ptr() as usize;
+    line[..offset].chars().count() + 1
+}
+
+/// Creates an error describing a problem at the given (one-based) line and
+/// column of a pattern file.
+fn parse_error(line: usize, column: usize, msg: impl AsRef<str>) -> IoError {
+    IoError::new(
+        IoErrorKind::InvalidData,
+        format!("line {line}, column {column}: {}", msg.as_ref()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the positions of the pattern's living cells.
+    fn living_cells(pattern: &Pattern) -> Vec<(usize, usize)> {
+        let mut cells: Vec<_> = pattern
+            .cells()
+            .filter(|(_, _, cell)| cell.is_alive())
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        cells.sort_by_key(|&(x, y)| (y, x));
+        cells
+    }
+
+    #[test]
+    fn read_sniffs_piped_rle() {
+        let content = "#C A glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+        let pattern = read(None, content.as_bytes()).unwrap();
+        assert_eq!(pattern.size(), (3, 3));
+        assert_eq!(
+            living_cells(&pattern),
+            [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn read_sniffs_piped_plaintext() {
+        let content = "!Name: Glider\n.O.\n..O\nOOO\n";
+        let pattern = read(None, content.as_bytes()).unwrap();
+        assert_eq!(pattern.size(), (3, 3));
+        assert_eq!(
+            living_cells(&pattern),
+            [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn read_sniffs_piped_life_106() {
+        let content = "#Life 1.06\n0 0\n1 0\n2 0\n";
+        let pattern = read(None, content.as_bytes()).unwrap();
+        assert_eq!(living_cells(&pattern), [(0, 0), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn read_prefers_the_path_extension_to_the_content() {
+        let content = "x = 3, y = 1\n3o!\n";
+        assert!(read(Some(Path::new("glider.cells")), content.as_bytes()).is_err());
+    }
+}
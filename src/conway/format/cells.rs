@@ -0,0 +1,55 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+//! Plaintext `.cells` patterns, as used by LifeWiki.
+//!
+//! Lines starting with `!` are comments. Every other line is a row of cells,
+//! where `.` is a dead cell and `O` (or `*`) is a living cell. Rows may be
+//! shorter than the pattern; missing cells are dead.
+
+use std::io::{BufRead, BufReader, Read, Result as IoResult};
+
+use crate::{board::Cell, format::parse_error, pattern::Pattern};
+
+/// The colour given to living cells, which `.cells` files do not specify.
+const COLOR: u32 = 1;
+
+/// Reads a `.cells` pattern.
+///
+/// Returns
+/// =======
+///
+/// `Ok(Pattern)` if the pattern was successfully read, or a
+/// [`std::io::Error`] if any problems occurred during reading or parsing.
+/// Parse errors report the line and column at which they occurred.
+pub fn read<R: Read>(reader: R) -> IoResult<Pattern> {
+    let mut pattern = Pattern::new(0, 0);
+    let mut y = 0;
+
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+
+        if line.starts_with('!') {
+            continue;
+        }
+
+        for (x, ch) in line.trim_end().chars().enumerate() {
+            match ch {
+                '.' => {}
+                'O' | '*' => pattern.set(x, y, Cell::new(COLOR)),
+                other => {
+                    return Err(parse_error(
+                        i + 1,
+                        x + 1,
+                        format!("invalid character '{other}'"),
+                    ));
+                }
+            }
+        }
+
+        pattern.grow(line.trim_end().chars().count(), y + 1);
+        y += 1;
+    }
+
+    Ok(pattern)
+}
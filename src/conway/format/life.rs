@@ -0,0 +1,149 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+//! Life 1.05 and Life 1.06 patterns.
+//!
+//! Both formats start with a `#Life 1.05` or `#Life 1.06` header line.
+//!
+//! In Life 1.05, `#D` lines are descriptions, `#N` selects Conway's rules and
+//! `#R` gives a rule in S/B notation. Each `#P x y` line starts a block of
+//! rows, where `.` is a dead cell and `*` is a living cell, with its top left
+//! corner at the given coordinates.
+//!
+//! In Life 1.06, every line other than the header gives the `x y` coordinates
+//! of one living cell.
+//!
+//! Coordinates may be negative in both formats; patterns are shifted so that
+//! their top left corner is at the origin.
+
+use std::io::{BufRead, BufReader, Read, Result as IoResult};
+
+use crate::{
+    board::Cell,
+    format::{column_of, parse_error},
+    pattern::Pattern,
+    rule::Rule,
+};
+
+/// The colour given to living cells, which Life files do not specify.
+const COLOR: u32 = 1;
+
+/// Reads a Life 1.05 pattern.
+///
+/// Returns
+/// =======
+///
+/// `Ok(Pattern)` if the pattern was successfully read, or a
+/// [`std::io::Error`] if any problems occurred during reading or parsing.
+/// Parse errors report the line and column at which they occurred.
+pub fn read_105<R: Read>(reader: R) -> IoResult<Pattern> {
+    let mut points = Vec::new();
+    let mut rule = None;
+    let mut block = (0, 0);
+    let mut y = 0;
+
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let line_number = i + 1;
+        let line = line.trim_end();
+
+        if let Some(directive) = line.strip_prefix('#') {
+            let mut words = directive.split_whitespace();
+            match words.next() {
+                Some("P") => {
+                    let x = parse_coordinate(line_number, line, words.next())?;
+                    let y0 = parse_coordinate(line_number, line, words.next())?;
+                    block = (x, y0);
+                    y = 0;
+                }
+                Some("R") => {
+                    let value = words.next().unwrap_or_default();
+                    rule = Some(
+                        value
+                            .parse::<Rule>()
+                            .map_err(|err| parse_error(line_number, 4, err))?,
+                    );
+                }
+                Some("N") => rule = Some(Rule::life()),
+                _ => {}
+            }
+            continue;
+        }
+
+        for (x, ch) in line.chars().enumerate() {
+            match ch {
+                '.' => {}
+                '*' => points.push((block.0 + x as isize, block.1 + y, Cell::new(COLOR))),
+                other => {
+                    return Err(parse_error(
+                        line_number,
+                        x + 1,
+                        format!("invalid character '{other}'"),
+                    ));
+                }
+            }
+        }
+        y += 1;
+    }
+
+    let pattern = Pattern::from_points(points);
+    Ok(match rule {
+        Some(rule) => pattern.with_rule(rule),
+        None => pattern,
+    })
+}
+
+/// Reads a Life 1.06 pattern.
+///
+/// Returns
+/// =======
+///
+/// `Ok(Pattern)` if the pattern was successfully read, or a
+/// [`std::io::Error`] if any problems occurred during reading or parsing.
+/// Parse errors report the line and column at which they occurred.
+pub fn read_106<R: Read>(reader: R) -> IoResult<Pattern> {
+    let mut points = Vec::new();
+
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let line_number = i + 1;
+
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        let x = parse_coordinate(line_number, &line, words.next())?;
+        let y = parse_coordinate(line_number, &line, words.next())?;
+
+        if let Some(extra) = words.next() {
+            return Err(parse_error(
+                line_number,
+                column_of(&line, extra),
+                format!("unexpected '{extra}' after coordinates"),
+            ));
+        }
+
+        points.push((x, y, Cell::new(COLOR)));
+    }
+
+    Ok(Pattern::from_points(points))
+}
+
+fn parse_coordinate(line_number: usize, line: &str, word: Option<&str>) -> IoResult<isize> {
+    let Some(word) = word else {
+        return Err(parse_error(
+            line_number,
+            line.chars().count() + 1,
+            "expected a coordinate",
+        ));
+    };
+
+    word.parse().map_err(|_| {
+        parse_error(
+            line_number,
+            column_of(line, word),
+            format!("'{word}' is not a valid coordinate"),
+        )
+    })
+}
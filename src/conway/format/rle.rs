@@ -0,0 +1,528 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+//! Run Length Encoded (RLE) patterns, as used by Golly and LifeWiki.
+//!
+//! An RLE file consists of optional `#` comment lines, a header line such as
+//! `x = 3, y = 3, rule = B3/S23`, and a body of runs. Each run is an optional
+//! count followed by a tag: `b` (or `.`) for dead cells, `o` for living cells,
+//! `$` for the end of a row and `!` for the end of the pattern.
+//!
+//! Multi-state RLE is also supported, in which the tags `A` to `X` represent
+//! states 1 to 24 and the two-letter tags `pA` to `yX` represent states 25 to
+//! 255. Each state is loaded as a living cell with the same colour, and `o` is
+//! equivalent to `A`.
+//!
+//! Under Generations rules such as Brian's Brain (`B2/S/C3`), the states are
+//! those of Golly instead: state 1 is a living cell, and each later state is a
+//! dying cell one generation older, up to one less than the rule's number of
+//! states.
+//!
+//! `#A` lines give the colour and age of dead cells (see [`crate::format`]).
+//! The rule may be followed by the board's topology in Golly's notation, such
+//! as `B3/S23:P80,40` for a bounded 80 by 40 board.
+
+use std::{
+    collections::BTreeMap,
+    io::{
+        BufRead, BufReader, Error as IoError, ErrorKind as IoErrorKind, Read, Result as IoResult,
+        Write,
+    },
+};
+
+use crate::{
+    board::Cell,
+    format::{parse_age, parse_error, parse_rule, rule_string, write_ages},
+    pattern::Pattern,
+    rule::Rule,
+};
+
+/// Maximum length of lines written to RLE files.
+const LINE_LEN: usize = 70;
+
+/// Reads an RLE pattern.
+///
+/// Arguments
+/// =========
+///
+/// - `reader` - A reader providing the pattern's RLE representation.
+///
+/// Returns
+/// =======
+///
+/// `Ok(Pattern)` if the pattern was successfully read, or a
+/// [`std::io::Error`] if any problems occurred during reading or parsing.
+/// Parse errors report the line and column at which they occurred.
+pub fn read<R: Read>(reader: R) -> IoResult<Pattern> {
+    let mut lines = BufReader::new(reader).lines().enumerate();
+    let mut ages = Vec::new();
+
+    // Skip comments and blank lines up to the header.
+    let (line_number, header) = loop {
+        match lines.next() {
+            Some((i, line)) => {
+                let line = line?;
+                let trimmed = line.trim();
+                if trimmed.starts_with("#A") {
+                    ages.push(parse_age(i + 1, trimmed)?);
+                } else if !trimmed.is_empty() && !trimmed.starts_with('#') {
+                    break (i + 1, line);
+                }
+            }
+            None => return Err(parse_error(1, 1, "missing 'x = ..., y = ...' header")),
+        }
+    };
+
+    let mut pattern = read_header(line_number, &header)?;
+    let states = pattern.rule().map_or(2, Rule::states);
+
+    let mut x = 0;
+    let mut y = 0;
+    let mut count: Option<usize> = None;
+    let mut prefix: Option<(usize, char)> = None;
+
+    'lines: for (i, line) in lines {
+        let line = line?;
+        let line_number = i + 1;
+
+        if line.trim_start().starts_with("#A") {
+            ages.push(parse_age(line_number, line.trim())?);
+            continue;
+        } else if line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        for (column, ch) in line.chars().enumerate() {
+            let column = column + 1;
+            let run = count.unwrap_or(1);
+
+            if let Some((prefix_column, prefix_ch)) = prefix.take() {
+                match ch {
+                    'A'..='X' => {
+                        let state =
+                            24 * (prefix_ch as u32 - 'p' as u32 + 1) + (ch as u32 - 'A' as u32 + 1);
+                        let Some(cell) = state_cell(state, states) else {
+                            return Err(parse_error(
+                                line_number,
+                                prefix_column,
+                                format!("state '{prefix_ch}{ch}' is out of range"),
+                            ));
+                        };
+                        set_run(&mut pattern, &mut x, y, run, cell);
+                        count = None;
+                        continue;
+                    }
+                    _ => {
+                        return Err(parse_error(
+                            line_number,
+                            column,
+                            format!("expected a state letter after '{prefix_ch}', found '{ch}'"),
+                        ));
+                    }
+                }
+            }
+
+            match ch {
+                '0'..='9' => {
+                    let digit = ch as usize - '0' as usize;
+                    let next = count.unwrap_or(0).checked_mul(10);
+                    match next.and_then(|count| count.checked_add(digit)) {
+                        Some(next) => count = Some(next),
+                        None => {
+                            return Err(parse_error(line_number, column, "run count is too large"));
+                        }
+                    }
+                    continue;
+                }
+                'b' | '.' => x = x.saturating_add(run),
+                'o' => set_run(&mut pattern, &mut x, y, run, Cell::new(1)),
+                'A'..='X' => match state_cell(ch as u32 - 'A' as u32 + 1, states) {
+                    Some(cell) => set_run(&mut pattern, &mut x, y, run, cell),
+                    None => {
+                        return Err(parse_error(
+                            line_number,
+                            column,
+                            format!("state '{ch}' is out of range for a rule with {states} states"),
+                        ));
+                    }
+                },
+                'p'..='y' => {
+                    prefix = Some((column, ch));
+                    continue;
+                }
+                '$' => {
+                    x = 0;
+                    y = y.saturating_add(run);
+                }
+                '!' => break 'lines,
+                ch if ch.is_whitespace() => continue,
+                other => {
+                    return Err(parse_error(
+                        line_number,
+                        column,
+                        format!("invalid character '{other}'"),
+                    ));
+                }
+            }
+
+            count = None;
+        }
+    }
+
+    for (x, y, cell) in ages {
+        pattern.set(x, y, cell);
+    }
+
+    Ok(pattern)
+}
+
+/// Writes a pattern in RLE format.
+///
+/// If every living cell has colour 1, the pattern is written as two-state RLE;
+/// otherwise it is written as multi-state RLE with each colour as a state.
+/// Patterns with Generations rules are written with Golly's states for living
+/// and dying cells instead, so the colours of living cells are not kept.
+///
+/// Runs are written from the living cells in order, without filling in the
+/// pattern's bounding box, so that sparse patterns spread over a huge area
+/// (such as those of unbounded universes) can be written.
+///
+/// Arguments
+/// =========
+///
+/// - `pattern` - The pattern to write.
+/// - `writer` - The writer to write the pattern to.
+/// - `ages` - Whether to write the colours and ages of dead cells.
+///
+/// Returns
+/// =======
+///
+/// `Ok(())` if the pattern was successfully written, or a [`std::io::Error`]
+/// if writing failed or the pattern has colours outside the range 1 to 255,
+/// which RLE cannot represent.
+pub fn write<W: Write>(pattern: &Pattern, mut writer: W, ages: bool) -> IoResult<()> {
+    let (width, height) = pattern.size();
+
+    let generations = pattern.rule().filter(|rule| rule.states() > 2);
+
+    // The states of the cells in the order they are written, by row and then
+    // by column. Later cells replace earlier ones at the same position.
+    let mut states = BTreeMap::new();
+    for (x, y, cell) in pattern.cells() {
+        let state = match (generations, cell.color) {
+            (Some(_), _) if cell.is_alive() => 1,
+            (Some(rule), Some(_)) if rule.refractory(cell.age) => cell.age + 1,
+            (None, Some(color)) if cell.is_alive() => color,
+            _ => continue,
+        };
+        if !(1..=255).contains(&state) {
+            return Err(IoError::new(
+                IoErrorKind::InvalidInput,
+                match generations {
+                    Some(_) => format!("state {state} cannot be written in RLE"),
+                    None => format!("colour {state} cannot be written in RLE"),
+                },
+            ));
+        }
+        states.insert((y, x), state);
+    }
+    let multistate = generations.is_some() || states.values().any(|&state| state != 1);
+
+    if ages {
+        write_ages(pattern, &mut writer)?;
+    }
+
+    write!(writer, "x = {width}, y = {height}")?;
+    if let Some(rule) = rule_string(pattern) {
+        write!(writer, ", rule = {rule}")?;
+    }
+    writeln!(writer)?;
+
+    let mut line = String::new();
+
+    // The run being built, as its state and length, and the position just
+    // after it. Rows end after their last living cell.
+    let mut run: Option<(u32, usize)> = None;
+    let (mut x, mut y) = (0, 0);
+
+    for ((cell_y, cell_x), state) in states {
+        if cell_y != y {
+            if let Some((state, len)) = run.take() {
+                push_run(&mut writer, &mut line, len, &tag(state, multistate))?;
+            }
+            push_run(&mut writer, &mut line, cell_y - y, "$")?;
+            (x, y) = (0, cell_y);
+        }
+
+        // The dead cells before this one, then the cell itself.
+        for (state, len) in [(0, cell_x - x), (state, 1)] {
+            if len == 0 {
+                continue;
+            } else if let Some((run_state, run_len)) = &mut run
+                && *run_state == state
+            {
+                *run_len += len;
+            } else if let Some((state, len)) = run.replace((state, len)) {
+                push_run(&mut writer, &mut line, len, &tag(state, multistate))?;
+            }
+        }
+        x = cell_x + 1;
+    }
+
+    if let Some((state, len)) = run {
+        push_run(&mut writer, &mut line, len, &tag(state, multistate))?;
+    }
+    push_run(&mut writer, &mut line, 1, "!")?;
+    writeln!(writer, "{line}")
+}
+
+/// Returns the RLE tag for a cell state.
+fn tag(state: u32, multistate: bool) -> String {
+    match (state, multistate) {
+        (0, false) => String::from("b"),
+        (_, false) => String::from("o"),
+        (0, true) => String::from("."),
+        (1..=24, true) => char::from(b'A' + (state - 1) as u8).to_string(),
+        (_, true) => {
+            let prefix = char::from(b'p' + ((state - 1) / 24 - 1) as u8);
+            let letter = char::from(b'A' + ((state - 1) % 24) as u8);
+            format!("{prefix}{letter}")
+        }
+    }
+}
+
+/// Appends a run to the current line, first writing the line out if the run
+/// would make it too long.
+fn push_run<W: Write>(writer: &mut W, line: &mut String, run: usize, tag: &str) -> IoResult<()> {
+    let token = if run > 1 {
+        format!("{run}{tag}")
+    } else {
+        tag.to_string()
+    };
+
+    if line.len() + token.len() > LINE_LEN {
+        writeln!(writer, "{line}")?;
+        line.clear();
+    }
+
+    line.push_str(&token);
+    Ok(())
+}
+
+/// Parses the `x = ..., y = ..., rule = ...` header line into an empty
+/// pattern of the declared size.
+fn read_header(line_number: usize, header: &str) -> IoResult<Pattern> {
+    let mut width = None;
+    let mut height = None;
+    let mut rule = None;
+    let mut column = 1;
+
+    for field in header.split(',') {
+        // The rule is the last field, and may itself contain commas.
+        let field = if field.trim_start().starts_with("rule") {
+            &header[column - 1..]
+        } else {
+            field
+        };
+
+        let Some((key, value)) = field.split_once('=') else {
+            return Err(parse_error(
+                line_number,
+                column,
+                format!("expected 'key = value', found '{}'", field.trim()),
+            ));
+        };
+
+        let value_column = column + key.len() + 1;
+
+        match key.trim() {
+            "x" => width = Some(parse_size(line_number, value_column, value)?),
+            "y" => height = Some(parse_size(line_number, value_column, value)?),
+            "rule" => {
+                rule = Some((value_column, value));
+                break;
+            }
+            other => {
+                return Err(parse_error(
+                    line_number,
+                    column,
+                    format!("unknown header field '{other}'"),
+                ));
+            }
+        }
+
+        column += field.len() + 1;
+    }
+
+    let (Some(width), Some(height)) = (width, height) else {
+        return Err(parse_error(
+            line_number,
+            1,
+            "header must give both 'x' and 'y'",
+        ));
+    };
+
+    let pattern = Pattern::new(width, height);
+    match rule {
+        Some((column, value)) => parse_rule(pattern, line_number, column, value),
+        None => Ok(pattern),
+    }
+}
+
+fn parse_size(line_number: usize, column: usize, value: &str) -> IoResult<usize> {
+    value.trim().parse().map_err(|_| {
+        parse_error(
+            line_number,
+            column,
+            format!("'{}' is not a valid size", value.trim()),
+        )
+    })
+}
+
+/// Returns the cell represented by a state, or `None` if the state is out of
+/// range.
+///
+/// Under Generations rules, which have more than two `states`, states 2 and up
+/// are dying cells that are still refractory, as in Golly. Otherwise each
+/// state is a living cell of that colour.
+fn state_cell(state: u32, states: u32) -> Option<Cell> {
+    match state {
+        1..=255 if states <= 2 => Some(Cell::new(state)),
+        1 => Some(Cell::new(1)),
+        state if state < states.min(256) => Some(Cell {
+            color: Some(1),
+            age: state - 1,
+        }),
+        _ => None,
+    }
+}
+
+/// Sets a run of cells starting at `x`, and moves `x` past it. Cells beyond
+/// the width given by the header are dropped, so that a huge run count cannot
+/// exhaust memory.
+fn set_run(pattern: &mut Pattern, x: &mut usize, y: usize, run: usize, cell: Cell) {
+    let (width, _) = pattern.size();
+    let end = x.saturating_add(run);
+    for cell_x in *x..end.min(width) {
+        pattern.set(cell_x, y, cell);
+    }
+    *x = end;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the positions and colours of the pattern's living cells.
+    fn living_cells(pattern: &Pattern) -> Vec<(usize, usize, u32)> {
+        let mut cells: Vec<_> = pattern
+            .cells()
+            .filter(|(_, _, cell)| cell.is_alive())
+            .map(|(x, y, cell)| (x, y, cell.color.unwrap()))
+            .collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    /// Writes a pattern as RLE and returns the text written.
+    fn written(pattern: &Pattern) -> String {
+        let mut written = Vec::new();
+        write(pattern, &mut written, false).unwrap();
+        String::from_utf8(written).unwrap()
+    }
+
+    #[test]
+    fn write_encodes_runs_and_row_ends() {
+        let glider = read("x = 3, y = 3\nbo$2bo$3o!\n".as_bytes()).unwrap();
+        assert_eq!(written(&glider), "x = 3, y = 3\nbo$2bo$3o!\n");
+
+        let mut pattern = Pattern::new(6, 5);
+        pattern.set(4, 1, Cell::new(1));
+        pattern.set(0, 4, Cell::new(2));
+        pattern.set(1, 4, Cell::new(2));
+        pattern.set(5, 4, Cell::new(30));
+        assert_eq!(written(&pattern), "x = 6, y = 5\n$4.A3$2B3.pF!\n");
+        assert_eq!(
+            living_cells(&read(written(&pattern).as_bytes()).unwrap()),
+            living_cells(&pattern)
+        );
+    }
+
+    #[test]
+    fn generations_states_are_dying_cells() {
+        let rle = "x = 4, y = 2, rule = B2/S345/C4\nABC$3.A!\n";
+        let pattern = read(rle.as_bytes()).unwrap();
+        let mut cells: Vec<_> = pattern
+            .cells()
+            .map(|(x, y, cell)| (x, y, cell.color, cell.age))
+            .collect();
+        cells.sort_unstable();
+        assert_eq!(
+            cells,
+            [
+                (0, 0, Some(1), 0),
+                (1, 0, Some(1), 1),
+                (2, 0, Some(1), 2),
+                (3, 1, Some(1), 0),
+            ]
+        );
+
+        assert_eq!(written(&pattern), rle);
+        assert!(read("x = 1, y = 1, rule = B2/S/C3\nC!\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn generations_patterns_round_trip() {
+        let rule: Rule = "Brian's Brain".parse().unwrap();
+        let mut pattern = Pattern::new(5, 3).with_rule(rule);
+        pattern.set(1, 0, Cell::new(1));
+        pattern.set(2, 0, Cell::new(1));
+        pattern.set(
+            3,
+            1,
+            Cell {
+                color: Some(1),
+                age: 1,
+            },
+        );
+        // Cells that are no longer refractory are not written.
+        pattern.set(
+            4,
+            2,
+            Cell {
+                color: Some(1),
+                age: 2,
+            },
+        );
+
+        let rle = written(&pattern);
+        assert_eq!(rle, "x = 5, y = 3, rule = B2/S/C3\n.2A$3.B!\n");
+
+        let read = read(rle.as_bytes()).unwrap();
+        let mut cells: Vec<_> = read
+            .cells()
+            .map(|(x, y, cell)| (x, y, cell.is_alive(), cell.age))
+            .collect();
+        cells.sort_unstable();
+        assert_eq!(cells, [(1, 0, true, 0), (2, 0, true, 0), (3, 1, false, 1)]);
+        assert_eq!(read.rule().map(Rule::states), Some(3));
+    }
+
+    #[test]
+    fn write_does_not_fill_the_bounding_box() {
+        let far = 1 << 40;
+        let mut pattern = Pattern::new(0, 0);
+        pattern.set(0, 0, Cell::new(1));
+        pattern.set(far, far, Cell::new(1));
+
+        let rle = written(&pattern);
+        assert_eq!(
+            rle,
+            format!("x = {0}, y = {0}\no{1}${1}bo!\n", far + 1, far)
+        );
+        assert_eq!(
+            living_cells(&read(rle.as_bytes()).unwrap()),
+            [(0, 0, 1), (far, far, 1)]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+//! Plain text patterns with coloured cells.
+//!
+//! Each line is a row of cells, where white space represents an empty cell and
+//! any other alphanumeric character represents a living cell. The colour of a
+//! living cell is determined by converting the character to a base-36 digit.
+//!
+//! Lines starting with `#` are not rows. `#R` gives the rule and topology as in
+//! RLE files (see [`crate::format::rle`]), `#A` gives the colour and age of a
+//! dead cell (see [`crate::format`]), and any other such line is a comment.
+
+use std::{
+    collections::BTreeMap,
+    io::{
+        BufRead, BufReader, Error as IoError, ErrorKind as IoErrorKind, Read, Result as IoResult,
+        Write,
+    },
+    iter,
+};
+
+use crate::{
+    board::Cell,
+    format::{parse_age, parse_error, parse_rule, rule_string, write_ages},
+    pattern::Pattern,
+};
+
+/// Reads a plain text pattern.
+///
+/// Returns
+/// =======
+///
+/// `Ok(Pattern)` if the pattern was successfully read, or a
+/// [`std::io::Error`] if any problems occurred during reading or parsing.
+/// Parse errors report the line and column at which they occurred.
+pub fn read<R: Read>(reader: R) -> IoResult<Pattern> {
+    let mut pattern = Pattern::new(0, 0);
+    let mut ages = Vec::new();
+    let mut y = 0;
+
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches(['\r', '\n']);
+        let line_number = i + 1;
+
+        if let Some(rule) = line.strip_prefix("#R") {
+            pattern = parse_rule(pattern, line_number, 3, rule)?;
+            continue;
+        } else if line.starts_with("#A") {
+            ages.push(parse_age(line_number, line)?);
+            continue;
+        } else if line.starts_with('#') {
+            continue;
+        }
+
+        for (x, ch) in line.chars().enumerate() {
+            match ch {
+                ch if ch.is_whitespace() => {}
+                ch if ch.is_alphanumeric() => match ch.to_digit(36) {
+                    Some(color) => pattern.set(x, y, Cell::new(color)),
+                    None => {
+                        return Err(parse_error(
+                            line_number,
+                            x + 1,
+                            format!("'{ch}' is not a base-36 colour"),
+                        ));
+                    }
+                },
+                other => {
+                    return Err(parse_error(
+                        line_number,
+                        x + 1,
+                        format!("invalid character '{other}'"),
+                    ));
+                }
+            }
+        }
+
+        y += 1;
+        pattern.grow(line.chars().count(), y);
+    }
+
+    for (x, y, cell) in ages {
+        pattern.set(x, y, cell);
+    }
+
+    Ok(pattern)
+}
+
+/// Writes a plain text pattern.
+///
+/// Rows end after their last living cell, except for the first, which is
+/// written at the full width of the pattern so that its size is preserved
+/// when it is read back. The rows are written from the living cells in order,
+/// without filling in the pattern's bounding box.
+///
+/// Arguments
+/// =========
+///
+/// - `pattern` - The pattern to write.
+/// - `writer` - The writer to write the pattern to.
+/// - `ages` - Whether to write the colours and ages of dead cells.
+///
+/// Returns
+/// =======
+///
+/// `Ok(())` if the pattern was successfully written, or a [`std::io::Error`]
+/// if writing failed or the pattern has colours that are not base-36 digits.
+pub fn write<W: Write>(pattern: &Pattern, mut writer: W, ages: bool) -> IoResult<()> {
+    let (width, height) = pattern.size();
+
+    // The living cells in the order they are written, by row and then by
+    // column. Later cells replace earlier ones at the same position.
+    let mut chars = BTreeMap::new();
+    for (x, y, cell) in pattern.cells().filter(|(_, _, cell)| cell.is_alive()) {
+        let color = cell.color.unwrap();
+        let Some(ch) = char::from_digit(color, 36) else {
+            return Err(IoError::new(
+                IoErrorKind::InvalidInput,
+                format!("colour {color} cannot be written as plain text"),
+            ));
+        };
+        chars.insert((y, x), ch);
+    }
+
+    if let Some(rule) = rule_string(pattern) {
+        writeln!(writer, "#R {rule}")?;
+    }
+    if ages {
+        write_ages(pattern, &mut writer)?;
+    }
+
+    let mut chars = chars.into_iter().peekable();
+    for y in 0..height {
+        let mut row = String::new();
+        while let Some(((_, x), ch)) = chars.next_if(|&((cell_y, _), _)| cell_y == y) {
+            row.extend(iter::repeat_n(' ', x - row.len()));
+            row.push(ch);
+        }
+        match y {
+            0 => writeln!(writer, "{row:width$}")?,
+            _ => writeln!(writer, "{row}")?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_ends_rows_after_their_last_cell_and_keeps_the_size() {
+        let mut pattern = Pattern::new(8, 4);
+        pattern.set(1, 0, Cell::new(1));
+        pattern.set(0, 2, Cell::new(2));
+        pattern.set(3, 2, Cell::new(1));
+        pattern.set(3, 2, Cell::new(11));
+
+        let mut written = Vec::new();
+        write(&pattern, &mut written, false).unwrap();
+        assert_eq!(String::from_utf8_lossy(&written), " 1      \n\n2  b\n\n");
+
+        let read = read(written.as_slice()).unwrap();
+        assert_eq!(read.size(), (8, 4));
+        let mut cells: Vec<_> = read
+            .cells()
+            .map(|(x, y, cell)| (x, y, cell.color))
+            .collect();
+        cells.sort_unstable();
+        assert_eq!(cells, [(0, 2, Some(2)), (1, 0, Some(1)), (3, 2, Some(11))]);
+    }
+}
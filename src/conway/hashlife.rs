@@ -0,0 +1,593 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::{
+    board::{Board, Cell},
+    census::Census,
+    cycle::Cycle,
+    engine::{Engine, Viewport},
+    neighborhood::Neighborhood,
+    pattern::Pattern,
+    rule::Rule,
+};
+
+/// Index of a node in [`HashLife::nodes`].
+type NodeId = u32;
+
+/// The leaf node for a dead cell.
+const DEAD: NodeId = 0;
+
+/// The leaf node for a living cell.
+const ALIVE: NodeId = 1;
+
+/// Number of nodes above which unused nodes and cached results are discarded.
+const NODE_LIMIT: usize = 1 << 21;
+
+/// Largest number of generations that may be advanced at once, so that the
+/// coordinates of the padded quadtree cannot overflow.
+pub const MAX_JUMP: usize = 1 << 40;
+
+/// A square quadtree node of `2^level` by `2^level` cells.
+///
+/// Nodes are immutable and shared: each distinct arrangement of cells is
+/// stored once, and identified by its [`NodeId`].
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    level: u8,
+
+    /// The north west, north east, south west and south east quadrants, for
+    /// nodes above level 0.
+    children: [NodeId; 4],
+
+    /// The number of living cells in the node.
+    population: u64,
+}
+
+/// An unbounded plane of cells simulated with Bill Gosper's HashLife
+/// algorithm.
+///
+/// The plane is stored as a quadtree in which identical regions share a node,
+/// and the future of each node is cached, so that repetitive patterns such as
+/// guns and the debris of methuselahs can be advanced by huge numbers of
+/// generations at once (see [`Engine::jump`]).
+///
+/// HashLife only supports Life-like rules with the usual eight neighbours, two
+/// states and no births without neighbours, and does not track the colours or
+/// ages of cells: every living cell has the same colour, and cells disappear
+/// as soon as they die.
+pub struct HashLife {
+    nodes: Vec<Node>,
+    index: HashMap<[NodeId; 4], NodeId>,
+
+    /// The cached result of [`HashLife::successor`] for each node and number
+    /// of generations.
+    results: HashMap<(NodeId, u8), NodeId>,
+
+    /// The empty node of each level.
+    empty: Vec<NodeId>,
+
+    root: NodeId,
+
+    /// The coordinates of the top left cell of the root node.
+    origin: (i64, i64),
+
+    rule: Rule,
+    color: u32,
+    generation: usize,
+}
+
+impl HashLife {
+    /// Creates a universe containing the living cells of the given pattern,
+    /// with its top left corner at the origin, simulated using the given rule.
+    ///
+    /// Returns an error if the rule is not supported by HashLife.
+    pub fn new(pattern: &Pattern, rule: Rule) -> Result<Self, String> {
+        // Cells born with no neighbours would fill the empty space that
+        // HashLife pads the universe with.
+        if rule.neighborhood() != Neighborhood::Moore(1) || rule.states() != 2 || rule.born(0) {
+            return Err(format!(
+                "HashLife only supports two-state rules with the usual eight neighbours and \
+                 without B0, not {rule}."
+            ));
+        }
+
+        let leaf = |population| Node {
+            level: 0,
+            children: [DEAD; 4],
+            population,
+        };
+
+        let mut hashlife = HashLife {
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin: (0, 0),
+            rule,
+            color: 1,
+            generation: 0,
+        };
+
+        let (width, height) = pattern.size();
+        let mut level = 3;
+        while (1 << level) < width.max(height) {
+            level += 1;
+        }
+        hashlife.root = hashlife.empty(level);
+
+        for (x, y, cell) in pattern.cells().filter(|(_, _, cell)| cell.is_alive()) {
+            hashlife.color = cell.color.unwrap_or(1);
+            hashlife.root = hashlife.set(hashlife.root, x as u64, y as u64, true);
+        }
+
+        Ok(hashlife)
+    }
+
+    /// Advances the universe by the given number of generations, in steps of
+    /// powers of two.
+    ///
+    /// Jumps of more than [`MAX_JUMP`] generations are made in several steps.
+    pub fn advance(&mut self, mut generations: usize) {
+        while generations > MAX_JUMP {
+            self.advance(MAX_JUMP);
+            generations -= MAX_JUMP;
+        }
+
+        for j in 0..=MAX_JUMP.ilog2() as u8 {
+            if generations & (1 << j) != 0 {
+                self.advance_pow2(j);
+            }
+        }
+    }
+
+    /// Advances the universe by `2^j` generations.
+    fn advance_pow2(&mut self, j: u8) {
+        if self.nodes.len() > NODE_LIMIT {
+            self.collect_garbage();
+        }
+
+        // Pad the root with empty space until it is large enough to step 2^j
+        // generations at once, and its living cells lie far enough inside it
+        // that none can escape the result.
+        loop {
+            let level = self.node(self.root).level;
+            if level >= j + 3 {
+                let inner = self.inner(self.root);
+                let inner = self.inner(inner);
+                if self.node(inner).population == self.node(self.root).population {
+                    break;
+                }
+            }
+
+            self.root = self.expand(self.root);
+            self.origin.0 -= 1 << (level - 1);
+            self.origin.1 -= 1 << (level - 1);
+        }
+
+        let level = self.node(self.root).level;
+        self.root = self.successor(self.root, j);
+        self.origin.0 += 1 << (level - 2);
+        self.origin.1 += 1 << (level - 2);
+        self.generation += 1 << j;
+    }
+
+    /// Returns the centre of a node of level `k` advanced by `2^j`
+    /// generations, as a node of level `k - 1`, where `j <= k - 2`.
+    ///
+    /// The node is split into nine overlapping sub-nodes of level `k - 1`,
+    /// whose centres are advanced recursively and combined into four nodes
+    /// that are advanced again if needed.
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let node = self.node(id);
+        let j = j.min(node.level - 2);
+
+        if node.population == 0 {
+            return self.empty(node.level - 1);
+        } else if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+
+        let result = if node.level == 2 {
+            self.step_4x4(id)
+        } else {
+            let [nw, ne, sw, se] = node.children.map(|child| self.node(child).children);
+
+            let parts = [
+                [nw[0], nw[1], nw[2], nw[3]],
+                [nw[1], ne[0], nw[3], ne[2]],
+                [ne[0], ne[1], ne[2], ne[3]],
+                [nw[2], nw[3], sw[0], sw[1]],
+                [nw[3], ne[2], sw[1], se[0]],
+                [ne[2], ne[3], se[0], se[1]],
+                [sw[0], sw[1], sw[2], sw[3]],
+                [sw[1], se[0], sw[3], se[2]],
+                [se[0], se[1], se[2], se[3]],
+            ];
+            let mut c = [DEAD; 9];
+            for (c, part) in c.iter_mut().zip(parts) {
+                let part = self.join(part);
+                *c = self.successor(part, j);
+            }
+
+            let quads = [
+                [c[0], c[1], c[3], c[4]],
+                [c[1], c[2], c[4], c[5]],
+                [c[3], c[4], c[6], c[7]],
+                [c[4], c[5], c[7], c[8]],
+            ];
+
+            let mut result = [DEAD; 4];
+            for (result, quad) in result.iter_mut().zip(quads) {
+                let quad = self.join(quad);
+                *result = if j < node.level - 2 {
+                    // The sub-nodes have already been advanced far enough,
+                    // so only their centres are needed.
+                    self.inner(quad)
+                } else {
+                    self.successor(quad, j)
+                };
+            }
+            self.join(result)
+        };
+
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// Advances the centre 2 by 2 cells of a level 2 node by one generation.
+    fn step_4x4(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (y, row) in cells.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = self.get(id, x as u64, y as u64);
+            }
+        }
+
+        let mut result = [DEAD; 4];
+        for (i, result) in result.iter_mut().enumerate() {
+            let (x, y) = (1 + i % 2, 1 + i / 2);
+            let mut count = 0;
+            for (dx, dy) in Neighborhood::Moore(1).offsets(0) {
+                let (nx, ny) = ((x as isize + dx) as usize, (y as isize + dy) as usize);
+                count += cells[ny][nx] as usize;
+            }
+
+            let alive = if cells[y][x] {
+                self.rule.survives(count)
+            } else {
+                self.rule.born(count)
+            };
+            *result = if alive { ALIVE } else { DEAD };
+        }
+
+        self.join(result)
+    }
+
+    fn node(&self, id: NodeId) -> Node {
+        self.nodes[id as usize]
+    }
+
+    /// Returns the node with the given quadrants, creating it if it does not
+    /// already exist.
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.index.get(&children) {
+            return id;
+        }
+
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level: self.node(children[0]).level + 1,
+            children,
+            population: children.iter().map(|&c| self.node(c).population).sum(),
+        });
+        self.index.insert(children, id);
+        id
+    }
+
+    /// Returns the empty node of the given level.
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let last = *self.empty.last().unwrap();
+            let next = self.join([last; 4]);
+            self.empty.push(next);
+        }
+        self.empty[level as usize]
+    }
+
+    /// Returns a node of the next level up with the given node at its centre.
+    fn expand(&mut self, id: NodeId) -> NodeId {
+        let node = self.node(id);
+        let e = self.empty(node.level - 1);
+        let [nw, ne, sw, se] = node.children;
+
+        let children = [
+            self.join([e, e, e, nw]),
+            self.join([e, e, ne, e]),
+            self.join([e, sw, e, e]),
+            self.join([se, e, e, e]),
+        ];
+        self.join(children)
+    }
+
+    /// Returns the centre of a node, as a node of the next level down.
+    fn inner(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self
+            .node(id)
+            .children
+            .map(|child| self.node(child).children);
+        self.join([nw[3], ne[2], sw[1], se[0]])
+    }
+
+    /// Returns `true` if the cell at the given coordinates within a node is
+    /// alive.
+    fn get(&self, id: NodeId, x: u64, y: u64) -> bool {
+        let node = self.node(id);
+        if node.level == 0 {
+            return id == ALIVE;
+        }
+
+        let half = 1 << (node.level - 1);
+        let quadrant = (y >= half) as usize * 2 + (x >= half) as usize;
+        self.get(node.children[quadrant], x % half, y % half)
+    }
+
+    /// Returns a copy of a node with the cell at the given coordinates alive
+    /// or dead.
+    fn set(&mut self, id: NodeId, x: u64, y: u64, alive: bool) -> NodeId {
+        let node = self.node(id);
+        if node.level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+
+        let half = 1 << (node.level - 1);
+        let quadrant = (y >= half) as usize * 2 + (x >= half) as usize;
+        let mut children = node.children;
+        children[quadrant] = self.set(children[quadrant], x % half, y % half, alive);
+        self.join(children)
+    }
+
+    /// Calls `f` with the coordinates of each living cell in a node whose top
+    /// left cell is at `(x, y)`, skipping quadrants outside the given bounds.
+    fn for_each_cell(
+        &self,
+        id: NodeId,
+        (x, y): (i64, i64),
+        bounds: (i64, i64, i64, i64),
+        f: &mut impl FnMut(i64, i64),
+    ) {
+        let node = self.node(id);
+        let size = 1i64 << node.level;
+        let (min_x, min_y, max_x, max_y) = bounds;
+
+        if node.population == 0 || x > max_x || y > max_y || x + size <= min_x || y + size <= min_y
+        {
+            return;
+        } else if node.level == 0 {
+            f(x, y);
+            return;
+        }
+
+        let half = size / 2;
+        for (i, &child) in node.children.iter().enumerate() {
+            let offset = ((i % 2) as i64 * half, (i / 2) as i64 * half);
+            self.for_each_cell(child, (x + offset.0, y + offset.1), bounds, f);
+        }
+    }
+
+    /// Discards nodes that are not part of the current root, along with all
+    /// cached results, by copying the root into a new store.
+    fn collect_garbage(&mut self) {
+        let old = std::mem::take(&mut self.nodes);
+        self.nodes = old[..2].to_vec();
+        self.index.clear();
+        self.results.clear();
+        self.empty = vec![DEAD];
+
+        let mut copied = HashMap::new();
+        self.root = self.copy(&old, self.root, &mut copied);
+    }
+
+    fn copy(&mut self, old: &[Node], id: NodeId, copied: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if old[id as usize].level == 0 {
+            return id;
+        } else if let Some(&id) = copied.get(&id) {
+            return id;
+        }
+
+        let children = old[id as usize]
+            .children
+            .map(|child| self.copy(old, child, copied));
+        let new = self.join(children);
+        copied.insert(id, new);
+        new
+    }
+}
+
+impl Engine for HashLife {
+    fn next(&mut self) {
+        self.advance(1);
+    }
+
+    fn jump(&mut self, generations: usize) {
+        self.advance(generations);
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Births and deaths happen inside cached nodes, so only living cells are
+    /// counted.
+    fn census(&self) -> Census {
+        let mut census = Census::default();
+        census.entry(self.color).live = self.node(self.root).population as usize;
+        census
+    }
+
+    /// Generations skipped by jumps are never seen, so cycles are not
+    /// detected in HashLife universes.
+    fn cycle(&self) -> Option<Cycle> {
+        None
+    }
+
+    fn to_pattern(&self) -> Pattern {
+        let mut points = Vec::new();
+        let everywhere = (i64::MIN, i64::MIN, i64::MAX, i64::MAX);
+        self.for_each_cell(self.root, self.origin, everywhere, &mut |x, y| {
+            points.push((x as isize, y as isize, Cell::new(self.color)));
+        });
+        Pattern::from_points(points).with_rule(self.rule.clone())
+    }
+
+    /// Living cells take the universe's colour, and dead cells disappear.
+    fn set_cell(&mut self, x: i64, y: i64, cell: Cell) {
+        // Pad the root with empty space until it contains the cell.
+        loop {
+            let size = 1 << self.node(self.root).level;
+            let (dx, dy) = (x - self.origin.0, y - self.origin.1);
+            if (0..size).contains(&dx) && (0..size).contains(&dy) {
+                self.root = self.set(self.root, dx as u64, dy as u64, cell.is_alive());
+                return;
+            }
+
+            self.root = self.expand(self.root);
+            self.origin.0 -= size / 2;
+            self.origin.1 -= size / 2;
+        }
+    }
+
+    fn into_editable(self: Box<Self>) -> Box<dyn Engine> {
+        self
+    }
+
+    /// Only the nodes of the current root are copied, without the cached
+    /// results, so that snapshots stay small.
+    fn snapshot(&self) -> Box<dyn Engine> {
+        let mut snapshot = HashLife {
+            nodes: self.nodes[..2].to_vec(),
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin: self.origin,
+            rule: self.rule.clone(),
+            color: self.color,
+            generation: self.generation,
+        };
+        snapshot.root = snapshot.copy(&self.nodes, self.root, &mut HashMap::new());
+        Box::new(snapshot)
+    }
+
+    fn view(&self, viewport: &Viewport) -> Cow<'_, Board> {
+        let mut pattern = Pattern::new(viewport.width, viewport.height);
+        let bounds = (
+            viewport.x,
+            viewport.y,
+            viewport.x + viewport.width as i64 - 1,
+            viewport.y + viewport.height as i64 - 1,
+        );
+
+        self.for_each_cell(self.root, self.origin, bounds, &mut |x, y| {
+            let (x, y) = ((x - viewport.x) as usize, (y - viewport.y) as usize);
+            pattern.set(x, y, Cell::new(self.color));
+        });
+
+        let board = Board::new(viewport.width, viewport.height)
+            .with_rule(self.rule.clone())
+            .with_pattern(&pattern);
+        Cow::Owned(board)
+    }
+
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        let mut bounds: Option<(i64, i64, i64, i64)> = None;
+        let everywhere = (i64::MIN, i64::MIN, i64::MAX, i64::MAX);
+        self.for_each_cell(self.root, self.origin, everywhere, &mut |x, y| {
+            bounds = Some(match bounds {
+                Some((min_x, min_y, max_x, max_y)) => {
+                    (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                }
+                None => (x, y, x, y),
+            });
+        });
+        bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library;
+
+    /// Returns the coordinates of the living cells of a HashLife universe.
+    fn living_cells(hashlife: &HashLife) -> Vec<(i64, i64)> {
+        let mut cells = Vec::new();
+        let everywhere = (i64::MIN, i64::MIN, i64::MAX, i64::MAX);
+        hashlife.for_each_cell(hashlife.root, hashlife.origin, everywhere, &mut |x, y| {
+            cells.push((x, y));
+        });
+        cells.sort_unstable();
+        cells
+    }
+
+    /// Advances a built-in pattern in HashLife and on a board large enough
+    /// that nothing reaches its edges, and checks that the same cells are
+    /// alive.
+    fn assert_matches_board(name: &str, generations: usize, size: usize, offset: usize) {
+        let index = library::PATTERNS.iter().position(|(n, _)| *n == name);
+        let pattern = library::get(index.unwrap());
+
+        let mut hashlife = HashLife::new(&pattern, Rule::life()).unwrap();
+        hashlife.advance(generations);
+
+        let mut board = Board::new(size, size)
+            .with_topology(crate::topology::Topology::Bounded)
+            .with_pattern_at(&pattern, offset as isize, offset as isize);
+        for _ in 0..generations {
+            board.next();
+        }
+
+        let mut expected = Vec::new();
+        for y in 0..size {
+            for x in 0..size {
+                if board.cell(x, y).is_alive() {
+                    expected.push((x as i64 - offset as i64, y as i64 - offset as i64));
+                }
+            }
+        }
+        expected.sort_unstable();
+
+        assert_eq!(hashlife.generation, generations);
+        assert_eq!(
+            hashlife.node(hashlife.root).population as usize,
+            expected.len()
+        );
+        assert_eq!(living_cells(&hashlife), expected);
+    }
+
+    #[test]
+    fn acorn_matches_a_board_after_a_power_of_two() {
+        assert_matches_board("Acorn", 1 << 7, 160, 70);
+    }
+
+    #[test]
+    fn gosper_glider_gun_matches_a_board_after_a_power_of_two() {
+        assert_matches_board("Gosper glider gun", 1 << 8, 140, 20);
+    }
+
+    #[test]
+    fn gosper_glider_gun_matches_a_board_after_several_steps() {
+        assert_matches_board("Gosper glider gun", 300, 160, 20);
+    }
+
+    #[test]
+    fn huge_jumps_do_not_overflow() {
+        let glider = library::get(0);
+        let mut hashlife = HashLife::new(&glider, Rule::life()).unwrap();
+        hashlife.advance(usize::MAX >> 20);
+        assert_eq!(hashlife.generation, usize::MAX >> 20);
+        assert_eq!(hashlife.node(hashlife.root).population, 5);
+    }
+}
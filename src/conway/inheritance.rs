@@ -0,0 +1,85 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use clap::ValueEnum;
+
+/// How a newborn cell's colour is chosen from the colours of its living
+/// neighbours, its parents.
+///
+/// Under the default policy, cells of different colours compete: a cell only
+/// survives with enough neighbours of its own colour, and is only born if its
+/// parents share a colour. The other policies are those of the established
+/// multi-colour variants of Life, in which colour plays no part in whether a
+/// cell lives or dies, but only in what colour it is born.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Inheritance {
+    /// A cell is only born if its parents all share a colour, and only
+    /// survives if it has enough neighbours of its own colour.
+    #[default]
+    Unanimous,
+
+    /// A cell takes the most common colour among its parents, as in
+    /// Immigration, and is not born if no colour is more common than the
+    /// others.
+    #[value(alias = "immigration")]
+    Majority,
+
+    /// A cell takes the most common colour among its parents, or, if no
+    /// colour is more common than the others, a colour on the board that none
+    /// of them has, as in QuadLife. The cell is not born if there is no such
+    /// colour.
+    #[value(name = "quadlife")]
+    QuadLife,
+}
+
+impl Inheritance {
+    /// Returns `true` if a living cell's survival depends only on neighbours
+    /// of its own colour, rather than on all of its living neighbours.
+    pub fn is_segregated(self) -> bool {
+        self == Inheritance::Unanimous
+    }
+
+    /// Returns the colour of a cell born to parents of the given colours.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `parents` - The colours of the cell's living neighbours.
+    /// - `palette` - The colours on the board, in order, from which QuadLife
+    ///   chooses a colour that none of the parents has.
+    ///
+    /// Returns
+    /// =======
+    ///
+    /// The newborn's colour, or `None` if the cell is not born because its
+    /// parents do not agree on a colour.
+    pub fn color(self, mut parents: impl Iterator<Item = u32>, palette: &[u32]) -> Option<u32> {
+        if self == Inheritance::Unanimous {
+            let first = parents.next()?;
+            return parents.all(|color| color == first).then_some(first);
+        }
+
+        // Count each colour, in order of colour.
+        let mut counts: Vec<(u32, usize)> = Vec::new();
+        for color in parents {
+            match counts.binary_search_by_key(&color, |&(color, _)| color) {
+                Ok(i) => counts[i].1 += 1,
+                Err(i) => counts.insert(i, (color, 1)),
+            }
+        }
+
+        let max = counts.iter().map(|&(_, count)| count).max()?;
+        let mut common = counts.iter().filter(|&&(_, count)| count == max);
+        let (color, _) = *common.next()?;
+
+        match common.next() {
+            None => Some(color),
+            Some(_) if self == Inheritance::QuadLife => palette.iter().copied().find(|&color| {
+                counts
+                    .binary_search_by_key(&color, |&(color, _)| color)
+                    .is_err()
+            }),
+            Some(_) => None,
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+//! A catalogue of classic Game of Life patterns, built into the program so
+//! that they can be placed without a pattern file.
+
+use std::str::FromStr;
+
+use crate::{format::cells, pattern::Pattern, rule::normalize_name};
+
+/// The built-in patterns, by name, in LifeWiki's plaintext `.cells` format.
+///
+/// Names are matched case-insensitively, ignoring spaces and punctuation, so
+/// "Gosper glider gun" and "gosper-glider-gun" are the same pattern.
+pub const PATTERNS: [(&str, &str); 8] = [
+    ("Glider", ".O.\n..O\nOOO\n"),
+    ("LWSS", ".O..O\nO....\nO...O\nOOOO.\n"),
+    (
+        "Pulsar",
+        "..OOO...OOO..\n\
+         .............\n\
+         O....O.O....O\n\
+         O....O.O....O\n\
+         O....O.O....O\n\
+         ..OOO...OOO..\n\
+         .............\n\
+         ..OOO...OOO..\n\
+         O....O.O....O\n\
+         O....O.O....O\n\
+         O....O.O....O\n\
+         .............\n\
+         ..OOO...OOO..\n",
+    ),
+    ("Pentadecathlon", "..O....O..\nOO.OOOO.OO\n..O....O..\n"),
+    (
+        "Gosper glider gun",
+        "........................O...........\n\
+         ......................O.O...........\n\
+         ............OO......OO............OO\n\
+         ...........O...O....OO............OO\n\
+         OO........O.....O...OO..............\n\
+         OO........O...O.OO....O.O...........\n\
+         ..........O.....O.......O...........\n\
+         ...........O...O....................\n\
+         ............OO......................\n",
+    ),
+    ("R-pentomino", ".OO\nOO.\n.O.\n"),
+    ("Acorn", ".O.....\n...O...\nOO..OOO\n"),
+    ("Diehard", "......O.\nOO......\n.O...OOO\n"),
+];
+
+/// Returns the built-in pattern with the given name, or `None` if there is no
+/// such pattern (see [`PATTERNS`]).
+pub fn find(name: &str) -> Option<Pattern> {
+    let name = normalize_name(name);
+    PATTERNS
+        .iter()
+        .position(|(named, _)| normalize_name(named) == name)
+        .map(get)
+}
+
+/// Returns the built-in pattern at the given index of [`PATTERNS`].
+pub fn get(index: usize) -> Pattern {
+    cells::read(PATTERNS[index].1.as_bytes()).expect("built-in patterns are valid")
+}
+
+/// A built-in pattern to place on the board before the simulation starts, as
+/// given to `--pattern`.
+///
+/// Stamps are written `NAME[/TRANSFORM][@X,Y][:COLOR]`, such as `glider`,
+/// `lwss/m@10,5` or `acorn/rr:3`. The transform turns the pattern a quarter
+/// turn clockwise for each `r` and flips it from left to right for `m`. The
+/// position gives the pattern's top left corner, counting from the top left
+/// of the board, and defaults to the centre of the board.
+#[derive(Clone, Debug)]
+pub struct Stamp {
+    pattern: Pattern,
+    position: Option<(isize, isize)>,
+    color: Option<u32>,
+}
+
+impl Stamp {
+    /// Places the stamp's pattern onto another pattern.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `pattern` - The pattern to place the stamp onto, usually the size of
+    ///   the board.
+    /// - `default_color` - The colour of the stamp's cells, if the stamp does
+    ///   not give one.
+    pub fn apply(&self, pattern: &mut Pattern, default_color: u32) {
+        let (stamp, x, y) = self.placed(pattern.size(), default_color);
+        pattern.stamp(&stamp, x, y);
+    }
+
+    /// Returns the stamp's pattern in its colour, and the position of its top
+    /// left corner on a board of the given size.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `size` - The width and height of the board, on which the stamp is
+    ///   centred if it does not give a position.
+    /// - `default_color` - The colour of the stamp's cells, if the stamp does
+    ///   not give one.
+    pub fn placed(
+        &self,
+        (width, height): (usize, usize),
+        default_color: u32,
+    ) -> (Pattern, isize, isize) {
+        let (stamp_width, stamp_height) = self.pattern.size();
+        let (x, y) = self.position.unwrap_or((
+            (width as isize - stamp_width as isize) / 2,
+            (height as isize - stamp_height as isize) / 2,
+        ));
+
+        let stamp = self
+            .pattern
+            .clone()
+            .with_color(self.color.unwrap_or(default_color));
+        (stamp, x, y)
+    }
+}
+
+/// Parses a stamp written as `NAME[/TRANSFORM][@X,Y][:COLOR]` (see [`Stamp`]).
+impl FromStr for Stamp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (s, color) = match s.rsplit_once(':') {
+            Some((s, color)) => match color.trim().parse() {
+                Ok(color) => (s, Some(color)),
+                Err(_) => return Err(format!("'{color}' is not a colour number.")),
+            },
+            None => (s, None),
+        };
+
+        let (s, position) = match s.rsplit_once('@') {
+            Some((s, position)) => (s, Some(parse_position(position)?)),
+            None => (s, None),
+        };
+
+        let (name, transform) = s.split_once('/').unwrap_or((s, ""));
+        let Some(mut pattern) = find(name) else {
+            let names = PATTERNS.map(|(name, _)| name).join(", ");
+            return Err(format!(
+                "'{name}' is not a built-in pattern. Try one of: {names}."
+            ));
+        };
+
+        for ch in transform.chars() {
+            pattern = match ch.to_ascii_lowercase() {
+                'r' => pattern.rotated(),
+                'm' => pattern.mirrored(),
+                _ => return Err(format!("'{ch}' is not a transform; use 'r' or 'm'.")),
+            };
+        }
+
+        Ok(Stamp {
+            pattern,
+            position,
+            color,
+        })
+    }
+}
+
+/// Parses a position on the board written as `X,Y`, such as `10,5`, counting
+/// from the top left of the board. Either coordinate may be negative.
+pub fn parse_position(s: &str) -> Result<(isize, isize), String> {
+    s.split_once(',')
+        .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+        .ok_or_else(|| format!("'{s}' is not a position such as 10,5."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_position_accepts_negative_coordinates() {
+        assert_eq!(parse_position("10,5"), Ok((10, 5)));
+        assert_eq!(parse_position("-3, -1"), Ok((-3, -1)));
+        assert_eq!(parse_position(" 0 ,-7"), Ok((0, -7)));
+    }
+
+    #[test]
+    fn parse_position_rejects_invalid_positions() {
+        assert!(parse_position("10").is_err());
+        assert!(parse_position("10,x").is_err());
+        assert!(parse_position("1.5,2").is_err());
+        assert!(parse_position("").is_err());
+    }
+}
@@ -1,39 +1,104 @@
 // Copyright (c) 2025 Jonathon Burnham Cobb
 // Licensed under the MIT-0 license.
 
-use std::{fs::OpenOptions, hash::RandomState, io::Result as IoResult, path::PathBuf};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{File, OpenOptions},
+    hash::RandomState,
+    io::{BufWriter, Error as IoError, ErrorKind, Result as IoResult, Write as _, stdin},
+    path::PathBuf,
+    time::Duration,
+};
 
-use clap::Parser;
-use crossterm::terminal;
-use doodles::common::term::{CommonArgs, WaitResult, cleanup_term, setup_term};
+use clap::{Parser, builder::RangedU64ValueParser, value_parser};
+use crossterm::{
+    event::{KeyCode, MouseButton, MouseEventKind},
+    terminal,
+};
+use doodles::common::term::{CommonArgs, WaitResult, capture_mouse, cleanup_term, setup_term};
 use doodles::error;
+use rand::{SeedableRng, rngs::StdRng};
 
-use crate::board::Board;
-use crate::renderer::render;
+use crate::board::{Board, Cell};
+use crate::census::Census;
+use crate::cycle::DEFAULT_MAX_PERIOD;
+use crate::editor::Editor;
+use crate::engine::{Engine, Viewport};
+use crate::hashlife::{HashLife, MAX_JUMP};
+use crate::inheritance::Inheritance;
+use crate::library::{Stamp, parse_position};
+use crate::packed::PackedBoard;
+use crate::pattern::Pattern;
+use crate::renderer::{board_size, cell_at, render, render_graph, render_message};
+use crate::rewind::{DEFAULT_REWIND, Rewind};
+use crate::rule::Rule;
+use crate::soup::{DEFAULT_DENSITY, Shape, Soup, Symmetry, parse_density, parse_size};
+use crate::topology::Topology;
+use crate::universe::Universe;
 
 mod board;
+mod census;
+mod cycle;
+mod editor;
+mod engine;
+mod format;
+mod hashlife;
+mod inheritance;
+mod library;
+mod neighborhood;
+mod packed;
+mod pattern;
 mod renderer;
+mod rewind;
+mod rule;
+mod soup;
+mod topology;
+mod universe;
 
 /// Conway's Game of Life simulator and renderer.
 ///
 /// This program reads an initial board configuration from a file, and simulates
-/// Conway's Game of Life, rendering the board to the terminal using colored
-/// output.
+/// Conway's Game of Life, or another Life-like rule, rendering the board to the
+/// terminal using colored output.
 ///
-/// In addition to the usual rules of Conway's Game of Life, this implementation
+/// In addition to the usual rules of the simulated rule, this implementation
 /// includes colored cells, which modifies the rules as follows:
 ///
-/// 1. Any live cell with fewer than two live neighbours **of the same color**
-///    dies, as if by underpopulation.
+/// 1. Any live cell survives if its number of live neighbours **of the same
+///    color** is one of the rule's survival counts, and otherwise dies.
 ///
-/// 2. Any live cell with two or three live neighbours **of the same color**
-///    survives.
+/// 2. Any live cell with more live neighbours **of any color** than the rule's
+///    largest survival count dies, as if by overpopulation.
 ///
-/// 3. Any live cell with more than three live neighbours **of any color** dies,
-///    as if by overpopulation.
+/// 3. Any dead cell whose number of live neighbours is one of the rule's birth
+///    counts becomes a live cell, if those neighbours are all **of the same
+///    color**.
 ///
-/// 4. Any dead cell with exactly three live neighbours **of the same color**
-///    becomes a live cell, as if by reproduction.
+/// With the default rule, `B3/S23`, this means that a live cell with fewer than
+/// two live neighbours of the same color dies, one with two or three survives,
+/// one with more than three live neighbours of any color dies, and a dead cell
+/// with exactly three live neighbours of the same color becomes a live cell.
+///
+/// `--inheritance` chooses one of the established multi-color variants of Life
+/// instead, in which cells live and die by the rule alone and are born with a
+/// color chosen from those of their neighbours.
+///
+/// While the simulation runs, pressing 'g' shows or hides a graph of the
+/// number of living cells of each color.
+///
+/// Pressing 'e' pauses the simulation to edit the board. The arrow keys move
+/// a cursor, space toggles the cell under it, and the digit keys choose the
+/// color of the cells drawn. 'v' marks a corner of a region, 'x' clears it and
+/// 'r' fills it with random cells (or the whole view if no region is marked).
+/// Tab picks one of the built-in patterns, which 'o' rotates, 'm' mirrors and
+/// 'p' stamps at the cursor. 'e' or Enter resumes the simulation.
+///
+/// Cells can also be drawn while the simulation runs by dragging with the left
+/// mouse button, and erased with the right. The digit keys choose the color of
+/// the cells drawn.
+///
+/// Pressing 'b' pauses the simulation and steps back through recent
+/// generations (see `--rewind`), from any of which it can be resumed.
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about)]
 struct Args {
@@ -42,13 +107,43 @@ struct Args {
 
     /// Path to the board file to render.
     ///
-    /// Board files should be plain text and contain rows of cells where white
-    /// spaces represent dead cells and any other alphanumeric character
-    /// represents a living cell. If no path is provided, a random board will be
-    /// generated.
+    /// The following formats are supported, and are recognised by the file's
+    /// extension or, failing that, by its content:
+    ///
+    /// - Plain text (`.txt`), with rows of cells where white spaces represent
+    ///   dead cells and any other alphanumeric character represents a living
+    ///   cell, coloured by its value as a base-36 digit.
+    ///
+    /// - Run length encoded patterns (`.rle`), as published by LifeWiki and
+    ///   Golly. In multi-state RLE files, each state is given its own colour.
+    ///
+    /// - LifeWiki plaintext patterns (`.cells`).
+    ///
+    /// - Life 1.05 and Life 1.06 patterns (`.lif` or `.life`).
+    ///
+    /// The pattern is centred on the board, unless `--offset` is given, and
+    /// its rule, if given, is used for the simulation. If the path is `-`, the
+    /// pattern is read from standard input and its format recognised by its
+    /// content. If no path is provided, a random board will be generated.
     #[arg()]
     path: Option<PathBuf>,
 
+    /// Position of the board file's top left corner, such as `10,5`, counting
+    /// from the top left of the board, instead of centring it.
+    ///
+    /// Cells placed outside a bounded board are discarded; unbounded universes
+    /// keep them.
+    #[arg(long, value_name = "X,Y", value_parser = parse_position, requires = "path")]
+    offset: Option<(isize, isize)>,
+
+    /// Size the board to fit the board file (and its offset), up to the size
+    /// of the terminal, instead of filling the terminal.
+    ///
+    /// Board files that give a topology are run on a board of the size they
+    /// give without this, unless `--topology` is given.
+    #[arg(long, requires = "path", conflicts_with_all = ["unbounded", "hashlife"])]
+    fit: bool,
+
     /// Maximum number of generations to simulate (0 for no limit).
     ///
     /// Once this limit is reached, the board will reset to the initial state
@@ -58,21 +153,289 @@ struct Args {
     /// converges to either a stable or oscillating state.
     #[arg(short = 'm', long, default_value_t = 0)]
     max: usize,
+
+    /// Longest period of oscillation to detect.
+    ///
+    /// When the board repeats a state from at most this many generations
+    /// earlier, the period and the generation at which the cycle started are
+    /// shown, and the board resets. Longer periods use more memory to detect.
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_PERIOD)]
+    max_period: usize,
+
+    /// Number of earlier frames kept for stepping backwards (0 to disable).
+    ///
+    /// Pressing 'b' while the simulation is running pauses it one frame back.
+    /// The left and right arrows (or 'b' and 'f') step backwards and
+    /// forwards, Home and End go to the oldest and latest frames, and Enter
+    /// resumes the simulation from the frame shown. Each frame kept is a copy
+    /// of the whole board, so more frames use more memory.
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_REWIND)]
+    rewind: usize,
+
+    /// Rule to simulate, overriding any rule given by the board file.
+    ///
+    /// Rules may be given in B/S notation, such as `B36/S23`, in the older
+    /// S/B notation, such as `23/36`, or by name: Life, HighLife, Seeds,
+    /// "Day & Night", "Life without Death", 2x2, Replicator, Diamoeba, Morley,
+    /// Maze, "Brian's Brain", "Star Wars", "Hexagonal Life", "Bosco's Rule" or
+    /// Majority. Defaults to Conway's Game of Life (`B3/S23`).
+    ///
+    /// Generations rules give the number of cell states too, as in `B2/S/C3`.
+    /// Dying cells pass through the extra states and cannot be born again
+    /// until they have done so.
+    ///
+    /// A `V` or `H` suffix, as in `B2/S34H`, counts neighbours in the von
+    /// Neumann (4 cells) or hexagonal (6 cells) neighbourhood instead of the
+    /// usual 8 cells. Hexagonal boards are drawn with offset rows. Larger than
+    /// Life rules, such as `R5,C0,M1,S34..58,B34..45,NM`, give the range of
+    /// the neighbourhood (R), the number of states (C), whether cells count
+    /// themselves (M), ranges of survival (S) and birth (B) counts, and the
+    /// neighbourhood's shape (N): M for Moore, N for von Neumann or H for
+    /// hexagonal.
+    #[arg(short = 'r', long)]
+    rule: Option<Rule>,
+
+    /// How newborn cells take their colour from their living neighbours.
+    ///
+    /// `unanimous` cells are only born if their neighbours share a colour,
+    /// and only survive with enough neighbours of their own colour. Under the
+    /// other policies, cells live and die by the rule alone, as in plain Life.
+    /// `majority` cells (as in Immigration) take the most common colour of
+    /// their neighbours, and are not born if no colour is more common than
+    /// the others. `quadlife` cells do the same, but on a tie take a colour
+    /// none of their neighbours has.
+    #[arg(long, value_enum, default_value_t)]
+    inheritance: Inheritance,
+
+    /// How the edges of the board are joined, overriding any topology given
+    /// by the board file. Defaults to a torus, on which cells leaving one edge
+    /// reappear at the opposite edge.
+    #[arg(short = 't', long, value_enum)]
+    topology: Option<Topology>,
+
+    /// Simulate an unbounded plane instead of a board the size of the
+    /// terminal, so that spaceships and guns are not cut off by its edges.
+    ///
+    /// The terminal shows a window into the plane, which can be panned with
+    /// the arrow keys and re-centred on the living cells by pressing 'c'.
+    /// Rules with B0 are not supported.
+    #[arg(short = 'u', long, conflicts_with = "topology")]
+    unbounded: bool,
+
+    /// Simulate an unbounded plane with the HashLife algorithm, which can
+    /// advance repetitive patterns such as guns and the debris of methuselahs
+    /// by thousands of generations at once. This implies `--unbounded`, which
+    /// may also be given.
+    ///
+    /// Only two-state rules with the usual eight neighbours are supported, and
+    /// all living cells are drawn in the same colour. Pressing '+' or '-'
+    /// doubles or halves the number of generations advanced per frame, up to
+    /// 2^30 with HashLife and 16 otherwise.
+    #[arg(long, conflicts_with = "topology")]
+    hashlife: bool,
+
+    /// Fast-forward this many generations before the simulation starts.
+    ///
+    /// Pressing 'j' while the simulation is running fast-forwards by this many
+    /// generations again, or by 1024 if not given. At most 2^40 generations
+    /// may be given.
+    #[arg(
+        short = 'j',
+        long,
+        value_name = "N",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..=MAX_JUMP as u64)
+    )]
+    jump: Option<usize>,
+
+    /// Save the board to this file on exit.
+    ///
+    /// The file is written in plain text format if its extension is `.txt`,
+    /// and in RLE format otherwise. Pressing 's' while the simulation is
+    /// running also saves the board to this file, or to
+    /// `conway-gen<GENERATION>.rle` if no path is given.
+    #[arg(long, value_name = "PATH")]
+    save_on_exit: Option<PathBuf>,
+
+    /// Place a built-in pattern on the board, which may be repeated to set up
+    /// several patterns at once.
+    ///
+    /// Patterns are written `NAME[/TRANSFORM][@X,Y][:COLOR]`, such as
+    /// `glider@10,5:2`. The names are Glider, LWSS, Pulsar, Pentadecathlon,
+    /// "Gosper glider gun", R-pentomino, Acorn and Diehard. Each `r` in the
+    /// transform turns the pattern a quarter turn clockwise, and `m` mirrors
+    /// it. The position is the pattern's top left corner, counted from the
+    /// top left of the board, and defaults to the centre. Each pattern is
+    /// given a different colour unless one is chosen.
+    ///
+    /// The patterns are placed over the board file, if one is given, and
+    /// otherwise on an empty board.
+    #[arg(short = 'p', long = "pattern", value_name = "STAMP")]
+    patterns: Vec<Stamp>,
+
+    /// Include the colours and ages of dead cells when saving, so that their
+    /// fading is restored along with the living cells.
+    #[arg(long)]
+    save_ages: bool,
+
+    /// Log the number of living cells of each colour, and the number born and
+    /// died, to this CSV file in every generation shown.
+    ///
+    /// Each row gives the run (the number of times the board has reset), the
+    /// generation, the colour and its counts.
+    #[arg(long, value_name = "PATH")]
+    stats: Option<PathBuf>,
+
+    /// Probability that each cell of a random board is alive.
+    #[arg(long, value_name = "P", default_value_t = DEFAULT_DENSITY, value_parser = parse_density)]
+    density: f64,
+
+    /// Number of colours of living cell on a random board.
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 2,
+        value_parser = value_parser!(u32).range(1..=6)
+    )]
+    colors: u32,
+
+    /// Size of the region of a random board that is filled, such as `40x20`.
+    ///
+    /// The region is centred on the board. Defaults to the whole board.
+    #[arg(long, value_name = "WxH", value_parser = parse_size)]
+    soup_size: Option<(usize, usize)>,
+
+    /// Shape of the region of a random board that is filled.
+    #[arg(long, value_enum, default_value_t)]
+    soup_shape: Shape,
+
+    /// Symmetry of a random board.
+    ///
+    /// C2 soups are unchanged by a half turn, C4 soups by a quarter turn, D4
+    /// soups by mirroring, and D8 soups by any quarter turn or mirroring. C4
+    /// and D8 soups are square.
+    #[arg(long, value_enum, default_value_t)]
+    symmetry: Symmetry,
+
+    /// Seed for the random number generator, so that the same random boards
+    /// are generated each time.
+    #[arg(long, value_name = "N")]
+    seed: Option<u64>,
 }
 
+/// Keys handled by the simulation, in addition to those that exit.
+const KEYS: [KeyCode; 21] = [
+    KeyCode::Char('s'),
+    KeyCode::Char('c'),
+    KeyCode::Char('e'),
+    KeyCode::Char('b'),
+    KeyCode::Char('g'),
+    KeyCode::Char('j'),
+    KeyCode::Char('+'),
+    KeyCode::Char('-'),
+    KeyCode::Char('1'),
+    KeyCode::Char('2'),
+    KeyCode::Char('3'),
+    KeyCode::Char('4'),
+    KeyCode::Char('5'),
+    KeyCode::Char('6'),
+    KeyCode::Char('7'),
+    KeyCode::Char('8'),
+    KeyCode::Char('9'),
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Up,
+    KeyCode::Down,
+];
+
+/// Number of censuses kept for the population graph.
+const GRAPH_HISTORY: usize = 1024;
+
+/// How long messages are shown before the board resets.
+const MESSAGE_DURATION: Duration = Duration::from_secs(2);
+
+/// Largest number of generations advanced per frame by engines that step one
+/// generation at a time.
+const MAX_STEP: usize = 16;
+
+/// Largest number of generations advanced per frame by HashLife, which skips
+/// ahead in powers of two.
+const MAX_HASHLIFE_STEP: usize = 1 << 30;
+
 fn main() -> IoResult<()> {
     let args = Args::parse();
 
+    let charset = args.common.charset();
+    let theme = args.common.theme();
+
+    let mut stats = match &args.stats {
+        Some(path) => match File::create(path) {
+            Ok(file) => {
+                let mut writer = BufWriter::new(file);
+                Census::write_csv_header(&mut writer)?;
+                Some(writer)
+            }
+            Err(err) => {
+                error!("Could not create '{}': {err}", path.display());
+                return Err(err);
+            }
+        },
+        None => None,
+    };
+
+    // Standard input can only be read once, so a piped board is read before
+    // the terminal is set up, and placed again after each reset.
+    let piped = match &args.path {
+        Some(path) if path.as_os_str() == "-" => match format::read(None, stdin().lock()) {
+            Ok(pattern) => Some(pattern),
+            Err(err) => {
+                error!("Could not read board from standard input: {err}");
+                return Err(err);
+            }
+        },
+        _ => None,
+    };
+
     setup_term()?;
+    capture_mouse()?;
+
+    // Errors from saving while the simulation runs are reported once the
+    // terminal has been restored.
+    let mut save_errors: Vec<(PathBuf, IoError)> = Vec::new();
+    let exit_pattern;
+
+    // Number of generations advanced per frame, changed with '+' and '-'.
+    let mut step: usize = 1;
+
+    // Colour of the cells drawn with the mouse or in edit mode, chosen with
+    // the digit keys. Defaults to the first colour on the board.
+    let mut paint_color = None;
+
+    // Random boards are drawn from one generator, so that a seed reproduces
+    // every board after a reset too.
+    let mut rand = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+
+    // The end of the frame being waited out, which is kept while mouse
+    // events are handled so that painting does not hold back the simulation.
+    let mut frame_deadline = None;
+
+    let mut show_graph = false;
+    let mut history = VecDeque::new();
+    let mut rewind = Rewind::new(args.rewind);
+    let mut run = 0;
 
     // Outer loop
     'outer: loop {
-        let (width, height) = terminal::size()?;
-        let mut board = Board::new(width as usize, height as usize);
-
-        board = if let Some(path) = &args.path {
-            // Load the board from the specified file.
-            let file = OpenOptions::new().read(true).open(&path);
+        run += 1;
+        history.clear();
+        rewind.clear();
+        let pattern = if let Some(pattern) = &piped {
+            Some(pattern.clone())
+        } else if let Some(path) = &args.path {
+            // Load the pattern from the specified file.
+            let file = OpenOptions::new().read(true).open(path);
             let file = match file {
                 Ok(file) => file,
                 Err(err) => {
@@ -81,37 +444,693 @@ fn main() -> IoResult<()> {
                 }
             };
 
-            match board.with_cells_from_file(file) {
-                Ok(board) => board,
+            match format::read(Some(path), file) {
+                Ok(pattern) => Some(pattern),
                 Err(err) => {
                     error!("Could not read board from '{}': {err}", path.display());
                     return Err(err);
                 }
             }
         } else {
-            let mut rand = rand::rng();
-            board.with_random_cells(&mut rand, 0.33)
+            None
         };
 
+        let rule = args
+            .rule
+            .clone()
+            .or_else(|| pattern.as_ref().and_then(|pattern| pattern.rule().cloned()))
+            .unwrap_or_default()
+            .with_inheritance(args.inheritance);
+
+        let topology = args
+            .topology
+            .or_else(|| pattern.as_ref().and_then(Pattern::topology))
+            .unwrap_or_default();
+
+        let (width, height) = terminal::size()?;
+        let (width, height) = board_size(width as usize, height as usize, rule.neighborhood());
+        let hexagonal = rule.neighborhood().is_hexagonal();
+
+        let bounded = !(args.unbounded || args.hashlife);
+
+        // A board file that gives its topology also gives the size of its
+        // board, which must fit in the terminal for it to behave as published.
+        let declared = pattern
+            .as_ref()
+            .filter(|_| bounded && args.topology.is_none())
+            .and_then(|pattern| Some((pattern.topology()?, pattern.size())));
+
+        let (width, height) = match (&pattern, declared) {
+            (Some(pattern), _) if args.fit => fit_size(
+                pattern.size(),
+                args.offset.unwrap_or((0, 0)),
+                (width, height),
+                hexagonal,
+            ),
+            (_, Some((topology, size))) => {
+                match declared_size(topology, size, (width, height), hexagonal) {
+                    Ok(size) => size,
+                    Err(err) => {
+                        error!("{err}");
+                        return Err(IoError::new(ErrorKind::InvalidInput, err));
+                    }
+                }
+            }
+            _ => (width, height),
+        };
+
+        let (pattern, board_origin) = if args.patterns.is_empty() && args.offset.is_none() {
+            (pattern, (0, 0))
+        } else {
+            let (placed, origin) = place(
+                pattern.as_ref(),
+                args.offset,
+                &args.patterns,
+                (width, height),
+                &rule,
+                bounded,
+            );
+            (Some(placed), origin)
+        };
+
+        let board = Board::new(width, height)
+            .with_rule(rule.clone())
+            .with_topology(topology)
+            .with_max_period(args.max_period);
+
+        let board = if let Some(pattern) = &pattern {
+            board.with_pattern(pattern)
+        } else {
+            let colors = theme
+                .choose_colors(&mut rand, 1..7, args.colors as usize)
+                .into_iter()
+                .map(|color| color as u32)
+                .collect::<Vec<_>>();
+            let (soup_width, soup_height) = args.soup_size.unwrap_or((width, height));
+            let soup = Soup::new(soup_width, soup_height)
+                .with_density(args.density)
+                .with_shape(args.soup_shape)
+                .with_symmetry(args.symmetry)
+                .generate(&mut rand, &colors);
+            board.with_pattern(&soup)
+        };
+
+        let mut viewport = Viewport::new(width, height);
+        viewport.pan(board_origin.0, board_origin.1);
+        let mut engine: Box<dyn Engine> = if args.hashlife {
+            let pattern = pattern.unwrap_or_else(|| board.to_pattern());
+            match HashLife::new(&pattern, rule) {
+                Ok(hashlife) => Box::new(hashlife),
+                Err(err) => {
+                    error!("{err}");
+                    return Err(IoError::new(ErrorKind::InvalidInput, err));
+                }
+            }
+        } else if args.unbounded {
+            let pattern = pattern.unwrap_or_else(|| board.to_pattern());
+            match Universe::new(&pattern, rule) {
+                Ok(universe) => Box::new(universe.with_max_period(args.max_period)),
+                Err(err) => {
+                    error!("{err}");
+                    return Err(IoError::new(ErrorKind::InvalidInput, err));
+                }
+            }
+        } else if let Some(packed) = PackedBoard::from_board(&board) {
+            Box::new(packed)
+        } else {
+            Box::new(board)
+        };
+
+        if let Some(generations) = args.jump {
+            engine.jump(generations);
+        }
+        // An unbounded universe is shown with the board file where it was
+        // placed, rather than centred on its cells.
+        if args.offset.is_none() {
+            viewport.centre_on(engine.bounds());
+        }
+        record(engine.as_ref(), &mut history, &mut stats, run)?;
+
         // Create a random state for rendering.
         let random_state = RandomState::new();
 
         // Inner simulation loop
         'sim: loop {
-            render(&board, &random_state)?;
+            render(&engine.view(&viewport), &random_state, charset, theme)?;
+            if show_graph {
+                let (columns, rows) = terminal::size()?;
+                let history = history.make_contiguous();
+                let (columns, rows) = (columns as usize, rows as usize);
+                render_graph(history, engine.generation(), columns, rows, charset, theme)?;
+            }
 
-            if args.common.wait()? == WaitResult::Exit {
-                break 'outer;
+            let (pan_x, pan_y) = (width.div_ceil(8) as i64, height.div_ceil(8) as i64);
+            let deadline = frame_deadline
+                .take()
+                .unwrap_or_else(|| args.common.frame_deadline());
+            match args.common.wait_keys_until(&KEYS, deadline)? {
+                WaitResult::Exit => {
+                    exit_pattern = engine.to_pattern();
+                    break 'outer;
+                }
+                WaitResult::Key(KeyCode::Char('s')) => {
+                    let path = args.save_on_exit.clone().unwrap_or_else(|| {
+                        PathBuf::from(format!("conway-gen{}.rle", engine.generation()))
+                    });
+                    if let Err(err) = format::save(&path, &engine.to_pattern(), args.save_ages) {
+                        save_errors.push((path, err));
+                    }
+                    continue 'sim;
+                }
+                WaitResult::Key(KeyCode::Char('c')) => {
+                    viewport.centre_on(engine.bounds());
+                    continue 'sim;
+                }
+                WaitResult::Key(KeyCode::Char('e')) => {
+                    rewind.save(engine.as_ref());
+                    engine = engine.into_editable();
+                    let color = paint_color.unwrap_or_else(|| first_color(engine.as_ref()));
+                    let mut editor = Editor::new(&viewport, color, hexagonal);
+                    let mut resume = false;
+                    while !resume {
+                        render(&engine.view(&viewport), &random_state, charset, theme)?;
+                        let (columns, rows) = terminal::size()?;
+                        editor.render(&viewport, columns as usize, rows as usize)?;
+
+                        match args.common.read_key(&editor::KEYS)? {
+                            WaitResult::Exit => {
+                                exit_pattern = engine.to_pattern();
+                                break 'outer;
+                            }
+                            WaitResult::Key(key) => {
+                                resume =
+                                    editor.handle(key, engine.as_mut(), &mut viewport, bounded);
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    paint_color = Some(editor.color());
+                    record(engine.as_ref(), &mut history, &mut stats, run)?;
+                    continue 'sim;
+                }
+                WaitResult::Key(KeyCode::Char(digit @ '1'..='9')) => {
+                    paint_color = Some(digit as u32 - '0' as u32);
+                    continue 'sim;
+                }
+                WaitResult::Mouse(ev) => {
+                    // The left button draws living cells and the right erases
+                    // them, without advancing the simulation or delaying its
+                    // next step.
+                    frame_deadline = Some(deadline);
+                    let cell = match ev.kind {
+                        MouseEventKind::Down(MouseButton::Left)
+                        | MouseEventKind::Drag(MouseButton::Left) => {
+                            Cell::new(paint_color.unwrap_or_else(|| first_color(engine.as_ref())))
+                        }
+                        MouseEventKind::Down(MouseButton::Right)
+                        | MouseEventKind::Drag(MouseButton::Right) => Cell::empty(),
+                        _ => continue 'sim,
+                    };
+
+                    if let Some((x, y)) = cell_at(ev.column as usize, ev.row as usize, hexagonal)
+                        && x < viewport.width
+                        && y < viewport.height
+                    {
+                        let (origin_x, origin_y) = viewport.origin(hexagonal);
+                        engine = engine.into_editable();
+                        engine.set_cell(origin_x + x as i64, origin_y + y as i64, cell);
+                    }
+                    continue 'sim;
+                }
+                WaitResult::Key(KeyCode::Char('g')) => {
+                    show_graph = !show_graph;
+                    continue 'sim;
+                }
+                WaitResult::Key(KeyCode::Char('b')) if rewind.is_empty() => {
+                    continue 'sim;
+                }
+                WaitResult::Key(KeyCode::Char('b')) => {
+                    // Steps back through the snapshots, where 0 is the
+                    // current generation, until one is chosen to resume from.
+                    let mut back = 1;
+                    loop {
+                        let shown = rewind.get(back).unwrap_or(engine.as_ref());
+                        render(&shown.view(&viewport), &random_state, charset, theme)?;
+                        let (columns, rows) = terminal::size()?;
+                        let (columns, rows) = (columns as usize, rows as usize);
+                        rewind.render(shown.generation(), back, columns, rows)?;
+
+                        match args.common.read_key(&rewind::KEYS)? {
+                            WaitResult::Exit => {
+                                exit_pattern = engine.to_pattern();
+                                break 'outer;
+                            }
+                            WaitResult::Key(KeyCode::Left | KeyCode::Char('b')) => {
+                                back = (back + 1).min(rewind.len());
+                            }
+                            WaitResult::Key(KeyCode::Right | KeyCode::Char('f')) => {
+                                back = back.saturating_sub(1);
+                            }
+                            WaitResult::Key(KeyCode::Home) => back = rewind.len(),
+                            WaitResult::Key(KeyCode::End) => back = 0,
+                            WaitResult::Key(KeyCode::Enter) => break,
+                            _ => {}
+                        }
+                    }
+
+                    // Each snapshot was followed by one census, which is
+                    // discarded from the graph along with the generations.
+                    if let Some(snapshot) = rewind.restore(back) {
+                        engine = snapshot;
+                        history.truncate(history.len().saturating_sub(back).max(1));
+                    }
+                    continue 'sim;
+                }
+                WaitResult::Key(KeyCode::Char('j')) => {
+                    rewind.save(engine.as_ref());
+                    engine.jump(args.jump.unwrap_or(1024));
+                    record(engine.as_ref(), &mut history, &mut stats, run)?;
+                    continue 'sim;
+                }
+                WaitResult::Key(KeyCode::Char('+')) => {
+                    let max_step = if args.hashlife {
+                        MAX_HASHLIFE_STEP
+                    } else {
+                        MAX_STEP
+                    };
+                    step = (step * 2).min(max_step);
+                    continue 'sim;
+                }
+                WaitResult::Key(KeyCode::Char('-')) => {
+                    step = (step / 2).max(1);
+                    continue 'sim;
+                }
+                // Boards always fill the terminal, so only unbounded universes
+                // can be panned.
+                WaitResult::Key(KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down)
+                    if bounded =>
+                {
+                    continue 'sim;
+                }
+                WaitResult::Key(key @ (KeyCode::Left | KeyCode::Right)) => {
+                    viewport.pan(if key == KeyCode::Left { -pan_x } else { pan_x }, 0);
+                    continue 'sim;
+                }
+                WaitResult::Key(key @ (KeyCode::Up | KeyCode::Down)) => {
+                    viewport.pan(0, if key == KeyCode::Up { -pan_y } else { pan_y });
+                    continue 'sim;
+                }
+                _ => {}
             }
 
-            board.next();
+            rewind.save(engine.as_ref());
+            engine.jump(step);
+            record(engine.as_ref(), &mut history, &mut stats, run)?;
+
+            if let Some(cycle) = engine.cycle() {
+                render(&engine.view(&viewport), &random_state, charset, theme)?;
+                let (columns, rows) = terminal::size()?;
+                render_message(&cycle.to_string(), columns as usize, rows as usize)?;
 
-            if board.converged() || args.max > 0 && board.generation() >= args.max {
+                if args.common.pause(MESSAGE_DURATION)? == WaitResult::Exit {
+                    exit_pattern = engine.to_pattern();
+                    break 'outer;
+                }
+                break 'sim;
+            } else if args.max > 0 && engine.generation() >= args.max {
                 break 'sim;
             }
         }
     }
     cleanup_term()?;
 
+    if let Some(stats) = &mut stats {
+        stats.flush()?;
+    }
+
+    if let Some(path) = &args.save_on_exit
+        && let Err(err) = format::save(path, &exit_pattern, args.save_ages)
+    {
+        save_errors.push((path.clone(), err));
+    }
+
+    for (path, err) in &save_errors {
+        error!("Could not save board to '{}': {err}", path.display());
+    }
+
+    match save_errors.pop() {
+        Some((_, err)) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Records the census of the engine's current generation for the population
+/// graph, and logs it if `--stats` was given.
+///
+/// Arguments
+/// =========
+///
+/// - `engine` - The engine whose census to record.
+/// - `history` - The censuses of recent generations, oldest first.
+/// - `stats` - The CSV file to log the census to, if any.
+/// - `run` - The number of times the board has been reset.
+fn record(
+    engine: &dyn Engine,
+    history: &mut VecDeque<Census>,
+    stats: &mut Option<BufWriter<File>>,
+    run: usize,
+) -> IoResult<()> {
+    let census = engine.census();
+    if let Some(stats) = stats {
+        census.write_csv(stats, run, engine.generation())?;
+    }
+
+    history.push_back(census);
+    if history.len() > GRAPH_HISTORY {
+        history.pop_front();
+    }
     Ok(())
 }
+
+/// Returns the first colour of living cell in the engine's universe, or 1 if
+/// there are none, as the default colour to draw with.
+fn first_color(engine: &dyn Engine) -> u32 {
+    engine
+        .census()
+        .colors()
+        .find(|(_, counts)| counts.live > 0)
+        .map_or(1, |(color, _)| color)
+}
+
+/// Places the board file and the built-in patterns given on the command line
+/// (see `--offset` and `--pattern`) on one pattern, each built-in pattern in a
+/// colour of its own unless it names one.
+///
+/// On a bounded board, the pattern is the size of the board and cells beyond
+/// its edges are dropped. Unbounded universes keep every cell, so the pattern
+/// is as large as its cells need, starting from their top left.
+///
+/// Arguments
+/// =========
+///
+/// - `pattern` - The board file, if any.
+/// - `offset` - The position of the board file's top left corner on the
+///   board, or `None` to centre it.
+/// - `stamps` - The built-in patterns to place over the board file.
+/// - `size` - The width and height of the board.
+/// - `rule` - The rule the board is simulated with.
+/// - `bounded` - Whether the board is bounded rather than an unbounded
+///   universe.
+///
+/// Returns
+/// =======
+///
+/// The placed pattern, and the coordinates of the board's top left corner in
+/// it, where the viewport starts.
+fn place(
+    pattern: Option<&Pattern>,
+    offset: Option<(isize, isize)>,
+    stamps: &[Stamp],
+    (width, height): (usize, usize),
+    rule: &Rule,
+    bounded: bool,
+) -> (Pattern, (i64, i64)) {
+    if bounded {
+        let board = Board::new(width, height).with_rule(rule.clone());
+        let mut placed = match (pattern, offset) {
+            (Some(pattern), Some((x, y))) => board.with_pattern_at(pattern, x, y).to_pattern(),
+            (Some(pattern), None) => board.with_pattern(pattern).to_pattern(),
+            (None, _) => Pattern::new(width, height),
+        };
+        for (i, stamp) in stamps.iter().enumerate() {
+            stamp.apply(&mut placed, (i % 6) as u32 + 1);
+        }
+        return (placed, (0, 0));
+    }
+
+    // Cells are placed as on a board, including moving the board file by an
+    // even number of rows if it is hexagonal (see `Board::with_pattern_at`).
+    let hexagonal = rule.neighborhood().is_hexagonal();
+    let mut cells = HashMap::new();
+    if let Some(pattern) = pattern {
+        let (pattern_width, pattern_height) = pattern.size();
+        let (x, mut y) = offset.unwrap_or((
+            (width as isize - pattern_width as isize) / 2,
+            (height as isize - pattern_height as isize) / 2,
+        ));
+        if hexagonal {
+            y -= y.rem_euclid(2);
+        }
+        for (cell_x, cell_y, cell) in pattern.cells() {
+            cells.insert((cell_x as isize + x, cell_y as isize + y), cell);
+        }
+    }
+    for (i, stamp) in stamps.iter().enumerate() {
+        let (stamp, x, y) = stamp.placed((width, height), (i % 6) as u32 + 1);
+        for (cell_x, cell_y, cell) in stamp.cells() {
+            cells.insert((cell_x as isize + x, cell_y as isize + y), cell);
+        }
+    }
+
+    let min_x = cells.keys().map(|&(x, _)| x).min().unwrap_or(0);
+    let min_y = cells.keys().map(|&(_, y)| y).min().unwrap_or(0);
+    let min_y = if hexagonal {
+        min_y - min_y.rem_euclid(2)
+    } else {
+        min_y
+    };
+
+    let mut placed = Pattern::new(0, 0);
+    for ((x, y), cell) in cells {
+        placed.set((x - min_x) as usize, (y - min_y) as usize, cell);
+    }
+    (placed, (-min_x as i64, -min_y as i64))
+}
+
+/// Returns the size of the board declared by a board file along with its
+/// topology (see [`Topology::from_golly`]).
+///
+/// Cylinders are unbounded vertically, so they are as tall as the terminal.
+/// Hexagonal boards are given an even number of rows, so that their top and
+/// bottom edges join.
+///
+/// Arguments
+/// =========
+///
+/// - `topology` - The topology given by the board file.
+/// - `size` - The width and height of the board file.
+/// - `max_size` - The largest board that fits in the terminal.
+/// - `hexagonal` - Whether the board is drawn with offset rows.
+///
+/// Returns
+/// =======
+///
+/// The size of the board, or an error message if it does not fit in the
+/// terminal.
+fn declared_size(
+    topology: Topology,
+    (width, height): (usize, usize),
+    (max_width, max_height): (usize, usize),
+    hexagonal: bool,
+) -> Result<(usize, usize), String> {
+    let height = match topology {
+        Topology::Cylinder => max_height,
+        _ if hexagonal => height.next_multiple_of(2),
+        _ => height,
+    };
+
+    if width > max_width || height > max_height {
+        return Err(format!(
+            "The board file is for a {width}x{height} board, which does not fit in the \
+             terminal's {max_width}x{max_height}. Pass --topology to use a board the size of \
+             the terminal instead."
+        ));
+    }
+    Ok((width, height))
+}
+
+/// Returns the size of a board fitted to a pattern (see `--fit`).
+///
+/// The board reaches the far edges of the pattern once it is placed, keeping
+/// an even number of rows if it is hexagonal so that its top and bottom edges
+/// join, as [`Board::with_pattern_at`] moves hexagonal patterns by an even
+/// number of rows.
+///
+/// Arguments
+/// =========
+///
+/// - `pattern_size` - The width and height of the pattern.
+/// - `offset` - The position of the pattern's top left corner on the board.
+/// - `max_size` - The largest board that fits in the terminal.
+/// - `hexagonal` - Whether the board is drawn with offset rows.
+fn fit_size(
+    (pattern_width, pattern_height): (usize, usize),
+    (x, y): (isize, isize),
+    (max_width, max_height): (usize, usize),
+    hexagonal: bool,
+) -> (usize, usize) {
+    let y = if hexagonal { y - y.rem_euclid(2) } else { y };
+    let width = (pattern_width as isize + x).max(1) as usize;
+    let height = (pattern_height as isize + y).max(1) as usize;
+    let height = if hexagonal {
+        height.next_multiple_of(2)
+    } else {
+        height
+    };
+    (width.min(max_width), height.min(max_height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a 3 by 2 pattern of living cells.
+    fn block() -> Pattern {
+        let mut pattern = Pattern::new(3, 2);
+        for (x, y) in [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)] {
+            pattern.set(x, y, Cell::new(1));
+        }
+        pattern
+    }
+
+    #[test]
+    fn place_clips_an_offset_pattern_to_a_bounded_board() {
+        let (placed, origin) = place(
+            Some(&block()),
+            Some((-2, 9)),
+            &[],
+            (10, 10),
+            &Rule::life(),
+            true,
+        );
+        assert_eq!(placed.size(), (10, 10));
+        assert_eq!(
+            placed
+                .cells()
+                .filter(|(_, _, cell)| cell.is_alive())
+                .count(),
+            1
+        );
+        assert_eq!(origin, (0, 0));
+    }
+
+    #[test]
+    fn place_keeps_cells_outside_the_terminal_in_an_unbounded_universe() {
+        let (placed, origin) = place(
+            Some(&block()),
+            Some((-2, 9)),
+            &[],
+            (10, 10),
+            &Rule::life(),
+            false,
+        );
+        assert_eq!(placed.size(), (3, 2));
+        assert_eq!(origin, (2, -9));
+
+        // The viewport starts at the board's top left corner, so the pattern
+        // appears at its offset with its off-screen cells kept.
+        let universe = Universe::new(&placed, Rule::life()).unwrap();
+        let mut viewport = Viewport::new(10, 10);
+        viewport.pan(origin.0, origin.1);
+        assert_eq!(universe.census().colors().next().unwrap().1.live, 6);
+        assert_eq!(universe.bounds(), Some((0, 0, 2, 1)));
+        let view = universe.view(&viewport);
+        assert!(view.cell(0, 9).is_alive());
+        assert!(!view.cell(1, 9).is_alive());
+        assert!(!view.cell(0, 0).is_alive());
+    }
+
+    #[test]
+    fn place_keeps_stamps_and_even_rows_in_a_hexagonal_universe() {
+        let rule: Rule = "B2/S34H".parse().unwrap();
+        let stamp = "glider@20,-5".parse().unwrap();
+        let (placed, origin) = place(
+            Some(&block()),
+            Some((-4, 3)),
+            &[stamp],
+            (10, 10),
+            &rule,
+            false,
+        );
+        assert_eq!(placed.cells().count(), 11);
+
+        // The board file is moved up to row 2, and the pattern starts on an
+        // even row so that its rows stay offset the same way.
+        assert_eq!(origin, (4, 6));
+        assert!(placed.cells().any(|(x, y, _)| (x, y) == (0, 8)));
+    }
+
+    #[test]
+    fn declared_size_is_the_board_files_size() {
+        let terminal = (80, 24);
+        assert_eq!(
+            declared_size(Topology::Torus, (30, 20), terminal, false),
+            Ok((30, 20))
+        );
+        assert_eq!(
+            declared_size(Topology::Bounded, (80, 24), terminal, false),
+            Ok((80, 24))
+        );
+        assert_eq!(
+            declared_size(Topology::Cylinder, (30, 5), terminal, false),
+            Ok((30, 24))
+        );
+        assert_eq!(
+            declared_size(Topology::KleinBottle, (30, 7), terminal, true),
+            Ok((30, 8))
+        );
+        assert!(declared_size(Topology::Torus, (81, 20), terminal, false).is_err());
+        assert!(declared_size(Topology::ProjectivePlane, (30, 25), terminal, false).is_err());
+    }
+
+    #[test]
+    fn board_files_with_a_topology_declare_their_size() {
+        let rle = "x = 3, y = 3, rule = B3/S23:T30,20\nbo$2bo$3o!\n";
+        let pattern = format::read(None, rle.as_bytes()).unwrap();
+        assert_eq!(pattern.topology(), Some(Topology::Torus));
+        assert_eq!(pattern.size(), (30, 20));
+    }
+
+    #[test]
+    fn jump_is_limited_so_that_hashlife_cannot_overflow() {
+        assert!(Args::try_parse_from(["conway", "--jump", "1099511627776"]).is_ok());
+        assert!(Args::try_parse_from(["conway", "--jump", "1099511627777"]).is_err());
+        assert!(Args::try_parse_from(["conway", "--jump", "18446744073709551615"]).is_err());
+        assert!(Args::try_parse_from(["conway", "--jump", "0"]).is_err());
+    }
+
+    #[test]
+    fn hashlife_implies_unbounded() {
+        let args = Args::try_parse_from(["conway", "--unbounded", "--hashlife"]).unwrap();
+        assert!(args.unbounded && args.hashlife);
+        assert!(Args::try_parse_from(["conway", "--hashlife", "-t", "torus"]).is_err());
+    }
+
+    #[test]
+    fn fit_size_reaches_the_far_edges_of_the_pattern() {
+        assert_eq!(fit_size((10, 4), (0, 0), (80, 24), false), (10, 4));
+        assert_eq!(fit_size((10, 4), (3, 2), (80, 24), false), (13, 6));
+    }
+
+    #[test]
+    fn fit_size_shrinks_with_negative_offsets() {
+        assert_eq!(fit_size((10, 4), (-3, -1), (80, 24), false), (7, 3));
+        assert_eq!(fit_size((10, 4), (-20, -20), (80, 24), false), (1, 1));
+    }
+
+    #[test]
+    fn fit_size_is_limited_to_the_terminal() {
+        assert_eq!(fit_size((200, 100), (0, 0), (80, 24), false), (80, 24));
+        assert_eq!(fit_size((70, 20), (20, 10), (80, 24), false), (80, 24));
+    }
+
+    #[test]
+    fn fit_size_keeps_an_even_number_of_rows_when_hexagonal() {
+        assert_eq!(fit_size((5, 3), (0, 0), (39, 24), true), (5, 4));
+        // An odd offset is rounded down to an even row.
+        assert_eq!(fit_size((5, 3), (0, 3), (39, 24), true), (5, 6));
+        assert_eq!(fit_size((5, 3), (0, -1), (39, 24), true), (5, 2));
+        assert_eq!(fit_size((5, 30), (0, 0), (39, 24), true), (5, 24));
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+/// The set of cells around a cell that count as its neighbours.
+///
+/// Each neighbourhood has a range, which is 1 for the usual neighbourhoods and
+/// larger for Larger than Life rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Cells within the range both horizontally and vertically, i.e. the 8
+    /// surrounding cells at range 1.
+    Moore(usize),
+
+    /// Cells within the range in Manhattan distance, i.e. the 4 orthogonally
+    /// adjacent cells at range 1.
+    VonNeumann(usize),
+
+    /// Cells within the range on a hexagonal grid, i.e. the 6 surrounding
+    /// cells at range 1.
+    ///
+    /// The grid is stored in rows, with odd rows offset half a cell to the
+    /// right of even rows.
+    Hexagonal(usize),
+}
+
+impl Neighborhood {
+    /// Returns the range of the neighbourhood.
+    pub fn range(self) -> usize {
+        match self {
+            Neighborhood::Moore(range)
+            | Neighborhood::VonNeumann(range)
+            | Neighborhood::Hexagonal(range) => range,
+        }
+    }
+
+    /// Returns `true` for hexagonal neighbourhoods, whose boards are drawn with
+    /// offset rows.
+    pub fn is_hexagonal(self) -> bool {
+        matches!(self, Neighborhood::Hexagonal(_))
+    }
+
+    /// Returns the number of neighbours each cell has.
+    pub fn size(self) -> usize {
+        self.offsets(0).len()
+    }
+
+    /// Returns the offsets from a cell in row `y` to each of its neighbours.
+    ///
+    /// Only hexagonal neighbourhoods depend on the row, as the offsets of odd
+    /// and even rows differ.
+    pub fn offsets(self, y: usize) -> Vec<(isize, isize)> {
+        let range = self.range() as isize;
+        let mut offsets = Vec::new();
+
+        for dy in -range..=range {
+            for dx in -range..=range {
+                let within = match self {
+                    Neighborhood::Moore(_) => true,
+                    Neighborhood::VonNeumann(_) => dx.abs() + dy.abs() <= range,
+                    Neighborhood::Hexagonal(_) => hex_distance(y, dx, dy) <= range,
+                };
+
+                if within && (dx, dy) != (0, 0) {
+                    offsets.push((dx, dy));
+                }
+            }
+        }
+
+        offsets
+    }
+}
+
+impl Default for Neighborhood {
+    fn default() -> Self {
+        Neighborhood::Moore(1)
+    }
+}
+
+/// Returns the distance on a hexagonal grid from a cell in row `y` to the cell
+/// offset from it by `dx` and `dy`, where odd rows are offset to the right.
+fn hex_distance(y: usize, dx: isize, dy: isize) -> isize {
+    // Convert both cells to axial coordinates, in which the third cube
+    // coordinate is -q - r.
+    let axial = |x: isize, y: isize| (x - (y - (y & 1)) / 2, y);
+
+    let (q0, r0) = axial(0, y as isize);
+    let (q1, r1) = axial(dx, y as isize + dy);
+    let (dq, dr) = (q1 - q0, r1 - r0);
+
+    (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+}
@@ -0,0 +1,457 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use std::{
+    borrow::Cow,
+    hash::{DefaultHasher, Hasher},
+    mem,
+};
+
+use bitvec::vec::BitVec;
+
+use crate::{
+    board::{Board, Cell, MAX_AGE},
+    census::{Census, Counts},
+    cycle::{Cycle, CycleDetector},
+    engine::{Engine, Viewport},
+    neighborhood::Neighborhood,
+    pattern::Pattern,
+    rule::Rule,
+    topology::Topology,
+};
+
+/// A board of cells of a single colour, stored as one bit per cell.
+///
+/// Each row is packed into 64-bit words, so that the neighbours of 64 cells
+/// are counted at once with bitwise adders. The rows are surrounded by a
+/// border of cells copied from across the board's edges according to its
+/// [`Topology`], so that every cell's neighbours can be found by shifting the
+/// rows above, below and around it.
+///
+/// Only two-state rules with the usual eight neighbours are supported. The
+/// ages of dead cells are not simulated, but the generation in which each
+/// cell died is recorded so that they fade in the same way as on a [`Board`].
+#[derive(Clone)]
+pub struct PackedBoard {
+    width: usize,
+    height: usize,
+
+    /// The number of words in each row, including the border.
+    row_words: usize,
+
+    /// The rows of cells, including the border, where the cell at `(x, y)` is
+    /// bit `x + 1` of row `y + 1`.
+    cells: BitVec<u64>,
+
+    /// The next generation's rows, while they are being computed.
+    next_cells: BitVec<u64>,
+
+    /// The mask of the bits in each row's words that hold cells rather than
+    /// the border.
+    interior: Vec<u64>,
+
+    /// The value of [`PackedBoard::clock`] at which each cell last died, or 0
+    /// if it has never been alive.
+    deaths: Vec<usize>,
+
+    /// The birth and survival rules, indexed by the number of living
+    /// neighbours.
+    birth: [bool; 9],
+    survival: [bool; 9],
+
+    rule: Rule,
+    topology: Topology,
+    color: u32,
+    generation: usize,
+    cycles: CycleDetector<Vec<u64>>,
+    census: Census,
+}
+
+impl PackedBoard {
+    /// Creates a packed copy of the given board.
+    ///
+    /// Returns `None` if the board's rule is not supported, or if its cells
+    /// are not all the same colour.
+    pub fn from_board(board: &Board) -> Option<Self> {
+        let rule = board.rule().clone();
+        if rule.neighborhood() != Neighborhood::Moore(1) || rule.states() != 2 {
+            return None;
+        }
+
+        let (width, height) = board.size();
+        let mut color = None;
+        for y in 0..height {
+            for x in 0..width {
+                let cell_color = board.cell(x, y).color;
+                if cell_color.is_some() && color.is_some() && cell_color != color {
+                    return None;
+                }
+                color = color.or(cell_color);
+            }
+        }
+
+        let row_words = (width + 2).div_ceil(64);
+        let len = row_words * 64 * (height + 2);
+        let mut packed = PackedBoard {
+            width,
+            height,
+            row_words,
+            cells: BitVec::repeat(false, len),
+            next_cells: BitVec::repeat(false, len),
+            interior: interior_mask(width, row_words),
+            deaths: vec![0; width * height],
+            // As on a board, cells with no living neighbours have no colour to
+            // be born with.
+            birth: [0, 1, 2, 3, 4, 5, 6, 7, 8].map(|count| count > 0 && rule.born(count)),
+            survival: [0, 1, 2, 3, 4, 5, 6, 7, 8].map(|count| rule.survives(count)),
+            rule,
+            topology: board.topology(),
+            color: color.unwrap_or(1),
+            generation: 0,
+            cycles: CycleDetector::new(board.max_period()),
+            census: board.census().clone(),
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let cell = board.cell(x, y);
+                if cell.is_alive() {
+                    let i = packed.index(x, y);
+                    packed.cells.set(i, true);
+                } else if !cell.is_empty() {
+                    packed.deaths[y * width + x] = packed.clock() + 1 - cell.age as usize;
+                }
+            }
+        }
+        packed.fill_border();
+
+        Some(packed)
+    }
+
+    /// Advances the board to the next generation by one simulation step.
+    pub fn next(&mut self) {
+        let mut board_hasher = DefaultHasher::new();
+        for &word in self.cells.as_raw_slice() {
+            board_hasher.write_u64(word);
+        }
+        let state = || self.cells.as_raw_slice().to_vec();
+        self.cycles
+            .record(self.generation, board_hasher.finish(), state);
+
+        let row_words = self.row_words;
+        let interior = &self.interior;
+        let died = self.clock() + 1;
+        let current = self.cells.as_raw_slice();
+        let next = self.next_cells.as_raw_mut_slice();
+        let mut tally = Counts::default();
+
+        for y in 0..self.height {
+            let rows = [y, y + 1, y + 2].map(|row| &current[row * row_words..][..row_words]);
+            let next_row = &mut next[(y + 1) * row_words..][..row_words];
+            let mut carry = 0;
+
+            for i in 0..row_words {
+                // Bit j of each word holds column j of the row, and the cells
+                // to its west, centre and east are in padded columns j, j + 1
+                // and j + 2.
+                let [west, centre, east] = [0, 1, 2].map(|shift| {
+                    rows.map(|row| {
+                        let next = row.get(i + 1).copied().unwrap_or(0);
+                        if shift == 0 {
+                            row[i]
+                        } else {
+                            (row[i] >> shift) | (next << (64 - shift))
+                        }
+                    })
+                });
+
+                let counts = count_neighbors([
+                    west[0], centre[0], east[0], west[1], east[1], west[2], centre[2], east[2],
+                ]);
+
+                let mut alive = 0;
+                for (count, mask) in counts.iter().enumerate() {
+                    if self.birth[count] {
+                        alive |= mask & !centre[1];
+                    }
+                    if self.survival[count] {
+                        alive |= mask & centre[1];
+                    }
+                }
+
+                // Move the cells back into their padded columns.
+                let word = ((alive << 1) | carry) & interior[i];
+                carry = alive >> 63;
+
+                let mut deaths = rows[1][i] & !word & interior[i];
+                tally.live += word.count_ones() as usize;
+                tally.born += (word & !rows[1][i]).count_ones() as usize;
+                tally.died += deaths.count_ones() as usize;
+
+                while deaths != 0 {
+                    let x = i * 64 + deaths.trailing_zeros() as usize - 1;
+                    self.deaths[y * self.width + x] = died;
+                    deaths &= deaths - 1;
+                }
+
+                next_row[i] = word;
+            }
+        }
+
+        mem::swap(&mut self.cells, &mut self.next_cells);
+        self.fill_border();
+        self.census = Census::default();
+        *self.census.entry(self.color) = tally;
+        self.generation += 1;
+    }
+
+    /// Returns the board's current cells as a pattern of the same size, with
+    /// its rule and topology.
+    pub fn to_pattern(&self) -> Pattern {
+        let mut pattern = Pattern::new(self.width, self.height)
+            .with_rule(self.rule.clone())
+            .with_topology(self.topology);
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = self.cell(x, y);
+                if !cell.is_empty() {
+                    pattern.set(x, y, cell);
+                }
+            }
+        }
+        pattern
+    }
+
+    /// Returns the cell at the given coordinates, with the age it would have
+    /// on a [`Board`].
+    fn cell(&self, x: usize, y: usize) -> Cell {
+        if self.cells[self.index(x, y)] {
+            return Cell::new(self.color);
+        }
+
+        match self.deaths[y * self.width + x] {
+            0 => Cell::empty(),
+            died => match self.clock() + 1 - died {
+                age if age > MAX_AGE as usize => Cell::empty(),
+                age => Cell {
+                    color: Some(self.color),
+                    age: age as u32,
+                },
+            },
+        }
+    }
+
+    /// Returns the index of the bit holding the cell at the given coordinates.
+    fn index(&self, x: usize, y: usize) -> usize {
+        (y + 1) * self.row_words * 64 + x + 1
+    }
+
+    /// Returns a counter that increases by one each generation, starting from
+    /// [`MAX_AGE`] so that the cells of the initial board can have died before
+    /// it.
+    fn clock(&self) -> usize {
+        self.generation + MAX_AGE as usize
+    }
+
+    /// Copies the cells across the board's edges into the border, or clears
+    /// them if the edge is not joined to another.
+    fn fill_border(&mut self) {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let top_and_bottom = (-1..=width).flat_map(|x| [(x, -1), (x, height)]);
+        let sides = (0..height).flat_map(|y| [(-1, y), (width, y)]);
+
+        for (x, y) in top_and_bottom.chain(sides) {
+            let alive = self
+                .topology
+                .locate(x, y, self.width, self.height)
+                .is_some_and(|(x, y)| self.cells[self.index(x, y)]);
+            let i = (y + 1) as usize * self.row_words * 64 + (x + 1) as usize;
+            self.cells.set(i, alive);
+        }
+    }
+}
+
+impl Engine for PackedBoard {
+    fn next(&mut self) {
+        PackedBoard::next(self);
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn census(&self) -> Census {
+        self.census.clone()
+    }
+
+    fn cycle(&self) -> Option<Cycle> {
+        self.cycles.cycle()
+    }
+
+    fn to_pattern(&self) -> Pattern {
+        PackedBoard::to_pattern(self)
+    }
+
+    /// Living cells take the board's colour.
+    fn set_cell(&mut self, x: i64, y: i64, cell: Cell) {
+        if !(0..self.width as i64).contains(&x) || !(0..self.height as i64).contains(&y) {
+            return;
+        }
+
+        let (x, y) = (x as usize, y as usize);
+        let i = self.index(x, y);
+        let counts = self.census.entry(self.color);
+        counts.live = counts.live + cell.is_alive() as usize - self.cells[i] as usize;
+
+        self.cells.set(i, cell.is_alive());
+        self.deaths[y * self.width + x] = if cell.is_alive() || cell.is_empty() {
+            0
+        } else {
+            self.clock() + 1 - cell.age as usize
+        };
+        self.fill_border();
+        self.cycles = CycleDetector::new(self.cycles.max_period());
+    }
+
+    /// Converts the board to a [`Board`] at the same generation. Cycle
+    /// detection starts again, as the boards record their states differently.
+    fn into_editable(self: Box<Self>) -> Box<dyn Engine> {
+        let board = Board::new(self.width, self.height)
+            .with_rule(self.rule.clone())
+            .with_topology(self.topology)
+            .with_max_period(self.cycles.max_period())
+            .with_pattern(&self.to_pattern())
+            .with_generation(self.generation)
+            .with_census(self.census.clone());
+        Box::new(board)
+    }
+
+    fn snapshot(&self) -> Box<dyn Engine> {
+        Box::new(self.clone())
+    }
+
+    /// The board always fills the terminal, so the viewport is ignored.
+    fn view(&self, _viewport: &Viewport) -> Cow<'_, Board> {
+        let board = Board::new(self.width, self.height)
+            .with_rule(self.rule.clone())
+            .with_topology(self.topology)
+            .with_pattern(&self.to_pattern());
+        Cow::Owned(board)
+    }
+
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        None
+    }
+}
+
+/// Returns the mask of the bits in each row's words that hold cells rather than
+/// the border, for a board of the given width.
+fn interior_mask(width: usize, row_words: usize) -> Vec<u64> {
+    (0..row_words)
+        .map(|i| {
+            (0..64)
+                .filter(|bit| (1..=width).contains(&(i * 64 + bit)))
+                .fold(0, |mask, bit| mask | 1 << bit)
+        })
+        .collect()
+}
+
+/// Counts the living cells among eight neighbours for each of 64 cells at
+/// once, using a tree of bitwise adders.
+///
+/// Arguments
+/// =========
+///
+/// - `neighbors` - Words whose bit `j` is set if the corresponding neighbour of
+///   cell `j` is alive.
+///
+/// Returns
+/// =======
+///
+/// Masks whose bit `j` is set if cell `j` has the number of living neighbours
+/// given by the mask's index.
+fn count_neighbors(neighbors: [u64; 8]) -> [u64; 9] {
+    let [a, b, c, d, e, f, g, h] = neighbors;
+
+    let (sum_abc, carry_abc) = full_add(a, b, c);
+    let (sum_def, carry_def) = full_add(d, e, f);
+    let (sum_gh, carry_gh) = (g ^ h, g & h);
+
+    let (ones, carry_ones) = full_add(sum_abc, sum_def, sum_gh);
+    let (twos_partial, fours_partial) = full_add(carry_abc, carry_def, carry_gh);
+    let (twos, carry_twos) = (twos_partial ^ carry_ones, twos_partial & carry_ones);
+    let (fours, eights) = (fours_partial ^ carry_twos, fours_partial & carry_twos);
+
+    [0, 1, 2, 3, 4, 5, 6, 7, 8].map(|count: u32| {
+        let bit = |word: u64, value: u32| if count & value != 0 { word } else { !word };
+        bit(ones, 1) & bit(twos, 2) & bit(fours, 4) & bit(eights, 8)
+    })
+}
+
+/// Adds three words bit by bit, returning the sum and carry bits.
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    (a ^ b ^ c, (a & b) | (c & (a ^ b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::soup::Soup;
+
+    /// Returns the positions, colours and ages of the pattern's cells.
+    fn cells(pattern: &Pattern) -> Vec<(usize, usize, Option<u32>, u32)> {
+        let mut cells: Vec<_> = pattern
+            .cells()
+            .filter(|(_, _, cell)| !cell.is_empty())
+            .map(|(x, y, cell)| (x, y, cell.color, cell.age))
+            .collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    /// Returns the counts of the census's colours that have any.
+    fn counts(census: &Census) -> Vec<(u32, Counts)> {
+        census
+            .colors()
+            .filter(|(_, counts)| *counts != Counts::default())
+            .collect()
+    }
+
+    /// Steps the same soup on a packed board and a dense one, and checks
+    /// that every generation matches, including the ages of dead cells.
+    fn assert_matches_board(topology: Topology) {
+        let soup = Soup::new(130, 40).generate(&mut StdRng::seed_from_u64(41), &[3]);
+        let mut board = Board::new(130, 40)
+            .with_topology(topology)
+            .with_pattern(&soup);
+        let mut packed = PackedBoard::from_board(&board).unwrap();
+
+        for _ in 0..300 {
+            board.next();
+            packed.next();
+            assert_eq!(cells(&packed.to_pattern()), cells(&board.to_pattern()));
+            assert_eq!(counts(&packed.census), counts(board.census()));
+        }
+        assert_eq!(packed.cycles.cycle(), board.cycle());
+    }
+
+    #[test]
+    fn matches_a_board_when_bounded() {
+        assert_matches_board(Topology::Bounded);
+    }
+
+    #[test]
+    fn matches_a_board_on_a_torus() {
+        assert_matches_board(Topology::Torus);
+    }
+
+    #[test]
+    fn from_board_rejects_several_colours() {
+        let soup = Soup::new(20, 10).generate(&mut StdRng::seed_from_u64(41), &[1, 2]);
+        let board = Board::new(20, 10).with_pattern(&soup);
+        assert!(PackedBoard::from_board(&board).is_none());
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use crate::{board::Cell, rule::Rule, topology::Topology};
+
+/// A pattern of cells that can be placed on a [`crate::board::Board`].
+///
+/// Patterns are usually read from a file (see [`crate::format`]). Unlike a
+/// board, a pattern has no fixed relationship to the terminal size and only
+/// stores the cells that are not empty.
+#[derive(Clone, Debug, Default)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    cells: Vec<(usize, usize, Cell)>,
+    rule: Option<Rule>,
+    topology: Option<Topology>,
+}
+
+impl Pattern {
+    /// Creates a new empty pattern with the given dimensions.
+    pub fn new(width: usize, height: usize) -> Self {
+        Pattern {
+            width,
+            height,
+            cells: Vec::new(),
+            rule: None,
+            topology: None,
+        }
+    }
+
+    /// Creates a pattern from cells at arbitrary coordinates, which may be
+    /// negative. The cells are shifted so that the top left corner of their
+    /// bounding box is at the origin.
+    pub fn from_points(points: Vec<(isize, isize, Cell)>) -> Self {
+        let min_x = points.iter().map(|&(x, _, _)| x).min().unwrap_or(0);
+        let min_y = points.iter().map(|&(_, y, _)| y).min().unwrap_or(0);
+
+        let mut pattern = Pattern::new(0, 0);
+        for (x, y, cell) in points {
+            pattern.set((x - min_x) as usize, (y - min_y) as usize, cell);
+        }
+        pattern
+    }
+
+    /// Returns a copy of this pattern that asks to be simulated using the
+    /// given rule.
+    pub fn with_rule(self, rule: Rule) -> Self {
+        Pattern {
+            rule: Some(rule),
+            ..self
+        }
+    }
+
+    /// Returns a copy of this pattern that asks to be simulated on a board with
+    /// the given topology.
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Pattern {
+            topology: Some(topology),
+            ..self
+        }
+    }
+
+    /// Sets the cell at the given coordinates, growing the pattern if they lie
+    /// outside its current bounds. Empty cells are ignored.
+    pub fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        if cell.is_empty() {
+            return;
+        }
+
+        self.width = self.width.max(x + 1);
+        self.height = self.height.max(y + 1);
+        self.cells.push((x, y, cell));
+    }
+
+    /// Copies the cells of another pattern into this one, with the other
+    /// pattern's top left corner at the given coordinates. Cells that fall
+    /// outside this pattern are dropped, and cells that overlap replace those
+    /// already there.
+    pub fn stamp(&mut self, other: &Pattern, x: isize, y: isize) {
+        for (cell_x, cell_y, cell) in other.cells() {
+            let cell_x = cell_x as isize + x;
+            let cell_y = cell_y as isize + y;
+
+            if (0..self.width as isize).contains(&cell_x)
+                && (0..self.height as isize).contains(&cell_y)
+            {
+                self.cells
+                    .retain(|&(x, y, _)| (x, y) != (cell_x as usize, cell_y as usize));
+                self.cells.push((cell_x as usize, cell_y as usize, cell));
+            }
+        }
+    }
+
+    /// Returns a copy of this pattern turned a quarter turn clockwise.
+    pub fn rotated(self) -> Self {
+        let height = self.height;
+        Pattern {
+            width: self.height,
+            height: self.width,
+            cells: self
+                .cells
+                .into_iter()
+                .map(|(x, y, cell)| (height - 1 - y, x, cell))
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Returns a copy of this pattern flipped from left to right.
+    pub fn mirrored(self) -> Self {
+        let width = self.width;
+        Pattern {
+            cells: self
+                .cells
+                .into_iter()
+                .map(|(x, y, cell)| (width - 1 - x, y, cell))
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Returns a copy of this pattern with every cell given the same colour.
+    pub fn with_color(self, color: u32) -> Self {
+        Pattern {
+            cells: self
+                .cells
+                .into_iter()
+                .map(|(x, y, cell)| {
+                    (
+                        x,
+                        y,
+                        Cell {
+                            color: Some(color),
+                            ..cell
+                        },
+                    )
+                })
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Grows the pattern to at least the given dimensions, so that trailing
+    /// empty rows and columns are kept.
+    pub fn grow(&mut self, width: usize, height: usize) {
+        self.width = self.width.max(width);
+        self.height = self.height.max(height);
+    }
+
+    /// Returns the dimensions of the pattern as (width, height).
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns an iterator over the non-empty cells in the pattern and their
+    /// coordinates.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, Cell)> + '_ {
+        self.cells.iter().copied()
+    }
+
+    /// Returns the rule the pattern asks to be simulated with, if any.
+    pub fn rule(&self) -> Option<&Rule> {
+        self.rule.as_ref()
+    }
+
+    /// Returns the topology the pattern asks to be simulated on, if any.
+    pub fn topology(&self) -> Option<Topology> {
+        self.topology
+    }
+}
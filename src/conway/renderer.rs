@@ -6,13 +6,20 @@ use std::{
     io::{Result as IoResult, Write as _, stdout},
 };
 
-use crate::board::Board;
+use crate::{board::Board, census::Census, neighborhood::Neighborhood};
 use crossterm::{
     cursor::MoveTo,
     queue,
-    style::{ContentStyle, PrintStyledContent},
+    style::{ContentStyle, PrintStyledContent, Stylize},
 };
-use doodles::common::term::{BOLD_STYLES, DIM_STYLES};
+use doodles::common::{
+    term::Charset,
+    theme::{Intensity, Theme},
+};
+
+/// Number of terminal rows taken by the population graph (see
+/// [`render_graph`]), including its legend.
+pub const GRAPH_HEIGHT: usize = 5;
 
 /// Glyphs used to represent cells.
 ///
@@ -20,6 +27,9 @@ use doodles::common::term::{BOLD_STYLES, DIM_STYLES};
 /// corresponds to a different age, with the first column used for living cells.
 /// When a cell is dead, it ages through the columns from left to right with
 /// each generation. Once it reaches the last column, it will remain there.
+///
+/// Under Generations rules, the refractory states are spread across the
+/// columns instead (see [`ramp_column`]).
 const CELL_GLYPHS: [[char; 12]; 8] = [
     ['█', '▓', '▒', '░', '⣿', '⡿', '⡾', '⡶', '⠶', '⠦', '⠢', '⠠'],
     ['█', '▓', '▒', '░', '⣿', '⣾', '⣺', '⡺', '⡪', '⢊', '⢈', '⠈'],
@@ -31,6 +41,29 @@ const CELL_GLYPHS: [[char; 12]; 8] = [
     ['█', '▓', '▒', '░', '⣿', '⣽', '⣼', '⡼', '⡬', '⠬', '⠌', '⠄'],
 ];
 
+/// ASCII equivalents of [`CELL_GLYPHS`], used when the terminal cannot display
+/// block or braille characters.
+const ASCII_CELL_GLYPHS: [[char; 12]; 8] = [
+    ['#', '@', '%', '&', '*', '+', '=', '~', '-', ':', ',', '.'],
+    ['#', '@', '%', '$', '*', '+', '=', '-', ';', ':', '.', '.'],
+    ['#', '@', '8', '&', 'x', '+', '=', '~', '-', ':', '.', '`'],
+    ['#', '@', '%', '&', 'o', '*', '=', '-', '~', ',', '.', '.'],
+    ['#', '@', '$', '%', '*', 'x', '+', '=', '-', ';', ',', '.'],
+    ['#', '@', '%', '8', '*', 'o', '+', '~', '-', ':', '.', '\''],
+    ['#', '@', '&', '%', '*', '+', 'x', '=', '-', ':', ',', '.'],
+    ['#', '@', '%', '&', 'o', '+', '=', '~', ';', ':', '.', '`'],
+];
+
+/// Glyphs used for living cells in monochrome mode.
+///
+/// Without colour, each colour is given its own glyph shape so that different
+/// colours can still be told apart. Dead cells fade through the usual
+/// [`CELL_GLYPHS`] ramp.
+const SPECIES_GLYPHS: [char; 8] = ['█', '●', '■', '▲', '◆', '★', '♣', '♥'];
+
+/// ASCII equivalents of [`SPECIES_GLYPHS`].
+const ASCII_SPECIES_GLYPHS: [char; 8] = ['#', 'O', 'X', 'A', 'W', '$', 'M', 'H'];
+
 /// Renders the given board to the terminal.
 ///
 /// Each cell is rendered using colored glyphs that indicate different ages.
@@ -39,7 +72,12 @@ const CELL_GLYPHS: [[char; 12]; 8] = [
 ///
 /// Although the simulation supports an arbitrary number of colors, only six
 /// distinct terminal colors are available. Colors will repeat if more than six
-/// are used.
+/// are used. In monochrome mode, living cells of each color are drawn with a
+/// distinct glyph shape instead.
+///
+/// Boards with a hexagonal neighbourhood are drawn with a space between
+/// cells, and with odd rows offset by one column, so that each cell sits
+/// between the two cells it neighbours in the rows above and below.
 ///
 /// This uses low-level terminal commands to render the board at a fixed
 /// position and size. It should render without flickering on most terminals.
@@ -52,22 +90,43 @@ const CELL_GLYPHS: [[char; 12]; 8] = [
 /// - `board` - The board to render.
 /// - `random_state` - A random state used to generate consistent random values
 ///   between frames (e.g., for selecting glyph variations).
+/// - `charset` - The characters the terminal is able to display.
+/// - `theme` - The theme used to style the cells.
 ///
 /// Returns
 /// =======
 ///
 /// `Ok(())` if the rendering was successful, or a [`std::io::Error`] if any
 /// problems occurred during terminal output.
-pub fn render(board: &Board, random_state: &RandomState) -> IoResult<()> {
+pub fn render(
+    board: &Board,
+    random_state: &RandomState,
+    charset: Charset,
+    theme: Theme,
+) -> IoResult<()> {
     let (width, height) = board.size();
     let mut stdout = stdout();
 
+    let (glyphs, species_glyphs) = match charset {
+        Charset::Unicode => (&CELL_GLYPHS, &SPECIES_GLYPHS),
+        Charset::Ascii => (&ASCII_CELL_GLYPHS, &ASCII_SPECIES_GLYPHS),
+    };
+
+    let hexagonal = board.rule().neighborhood().is_hexagonal();
+
     for y in 0..height {
         queue!(stdout, MoveTo(0, y as u16),)?;
 
         for x in 0..width {
             let cell = board.cell(x, y);
 
+            if hexagonal && (x > 0 || y % 2 == 1) {
+                queue!(
+                    stdout,
+                    PrintStyledContent(ContentStyle::default().apply(" "))
+                )?;
+            }
+
             if cell.is_empty() {
                 queue!(
                     stdout,
@@ -76,25 +135,29 @@ pub fn render(board: &Board, random_state: &RandomState) -> IoResult<()> {
                 continue;
             }
 
-            let color = cell.color.map(|color| (color as usize) % BOLD_STYLES.len());
+            let color = cell.color.unwrap() as usize;
             let style = if cell.is_alive() {
-                color.map(|i| BOLD_STYLES[i]).unwrap()
+                theme.style(color, Intensity::Bold)
             } else {
-                color.map(|i| DIM_STYLES[i]).unwrap()
+                theme.style(color, Intensity::Dim)
             };
 
             let col = if cell.is_alive() {
                 0
             } else {
-                ((cell.age - 1) as usize).min(CELL_GLYPHS[0].len() - 1)
+                ramp_column(cell.age, board.rule().states(), glyphs[0].len())
             };
 
             let mut hasher = random_state.build_hasher();
             x.hash(&mut hasher);
             y.hash(&mut hasher);
-            let row = hasher.finish() as usize % CELL_GLYPHS.len();
+            let row = hasher.finish() as usize % glyphs.len();
 
-            let glyph = CELL_GLYPHS[row][col];
+            let glyph = if theme.is_monochrome() && cell.is_alive() {
+                species_glyphs[color % species_glyphs.len()]
+            } else {
+                glyphs[row][col]
+            };
 
             queue!(stdout, PrintStyledContent(style.apply(glyph)))?;
         }
@@ -104,3 +167,228 @@ pub fn render(board: &Board, random_state: &RandomState) -> IoResult<()> {
 
     Ok(())
 }
+
+/// Renders a message centred on the bottom row of the terminal, over the
+/// board, in reverse video so that it stands out against any colour.
+///
+/// Arguments
+/// =========
+///
+/// - `message` - The message to show.
+/// - `width` - The width of the terminal in columns.
+/// - `height` - The height of the terminal in rows.
+pub fn render_message(message: &str, width: usize, height: usize) -> IoResult<()> {
+    let message = format!(" {message} ");
+    let x = width.saturating_sub(message.len()) / 2;
+    let mut stdout = stdout();
+
+    queue!(
+        stdout,
+        MoveTo(x as u16, height.saturating_sub(1) as u16),
+        PrintStyledContent(ContentStyle::default().reverse().apply(message))
+    )?;
+    stdout.flush()
+}
+
+/// Renders a cursor over the cell of a board drawn by [`render`], in reverse
+/// video so that it stands out against any colour.
+///
+/// Arguments
+/// =========
+///
+/// - `x` - The column of the cell on the board.
+/// - `y` - The row of the cell on the board.
+/// - `hexagonal` - Whether the board is drawn with offset rows.
+/// - `glyph` - The character to draw.
+pub fn render_cursor(x: usize, y: usize, hexagonal: bool, glyph: char) -> IoResult<()> {
+    let column = match hexagonal {
+        true => 2 * x + y % 2,
+        false => x,
+    };
+    let mut stdout = stdout();
+
+    queue!(
+        stdout,
+        MoveTo(column as u16, y as u16),
+        PrintStyledContent(ContentStyle::default().reverse().apply(glyph))
+    )?;
+    stdout.flush()
+}
+
+/// Returns the cell of a board drawn by [`render`] at the given terminal
+/// position, as `(x, y)`, or `None` if the position falls between the cells of
+/// a hexagonal board.
+pub fn cell_at(column: usize, row: usize, hexagonal: bool) -> Option<(usize, usize)> {
+    if !hexagonal {
+        return Some((column, row));
+    }
+
+    let column = column.checked_sub(row % 2)?;
+    column.is_multiple_of(2).then_some((column / 2, row))
+}
+
+/// Renders a graph of the number of living cells of each colour in recent
+/// generations over the bottom [`GRAPH_HEIGHT`] rows of the terminal, with a
+/// legend giving the latest counts.
+///
+/// Each column of the graph shows two generations using braille dots, or one
+/// using asterisks if the character set is ASCII. The vertical scale fits the
+/// largest population shown.
+///
+/// Arguments
+/// =========
+///
+/// - `history` - The censuses of recent generations, oldest first.
+/// - `generation` - The generation of the last census in `history`.
+/// - `width` - The width of the terminal in columns.
+/// - `height` - The height of the terminal in rows.
+/// - `charset` - The character set to draw with.
+/// - `theme` - The theme used to style each colour's dots, which stay
+///   distinguishable in monochrome mode.
+pub fn render_graph(
+    history: &[Census],
+    generation: usize,
+    width: usize,
+    height: usize,
+    charset: Charset,
+    theme: Theme,
+) -> IoResult<()> {
+    if height <= GRAPH_HEIGHT {
+        return Ok(());
+    }
+
+    let (samples_per_column, dots_per_row) = match charset {
+        Charset::Unicode => (2, 4),
+        Charset::Ascii => (1, 1),
+    };
+    let rows = GRAPH_HEIGHT - 1;
+    let levels = rows * dots_per_row;
+
+    let samples = &history[history.len().saturating_sub(width * samples_per_column)..];
+    let max = (samples.iter())
+        .flat_map(|census| census.colors().map(|(_, counts)| counts.live))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    // The dots set in each character of the graph, and how many each colour
+    // set, so that characters can take the colour with the most.
+    let mut dots = vec![(0u8, Vec::<(u32, usize)>::new()); rows * width];
+    for (i, census) in samples.iter().enumerate() {
+        let (column, side) = (i / samples_per_column, i % samples_per_column);
+
+        for (color, counts) in census.colors().filter(|(_, counts)| counts.live > 0) {
+            let level = counts.live * (levels - 1) / max;
+            let row = rows - 1 - level / dots_per_row;
+            let dot = dots_per_row - 1 - level % dots_per_row;
+
+            let (bits, colors) = &mut dots[row * width + column];
+            *bits |= if side == 0 {
+                [0x01, 0x02, 0x04, 0x40][dot]
+            } else {
+                [0x08, 0x10, 0x20, 0x80][dot]
+            };
+            match colors.iter_mut().find(|(c, _)| *c == color) {
+                Some((_, count)) => *count += 1,
+                None => colors.push((color, 1)),
+            }
+        }
+    }
+
+    let mut stdout = stdout();
+    let top = height.saturating_sub(GRAPH_HEIGHT);
+
+    // Legend
+    queue!(
+        stdout,
+        MoveTo(0, top as u16),
+        PrintStyledContent(ContentStyle::default().apply(" ".repeat(width)))
+    )?;
+    let mut legend = format!("gen {generation}");
+    queue!(
+        stdout,
+        MoveTo(0, top as u16),
+        PrintStyledContent(ContentStyle::default().apply(legend.clone()))
+    )?;
+    let swatch = if charset.is_ascii() { '#' } else { '■' };
+    for (color, counts) in history.last().into_iter().flat_map(Census::colors) {
+        let entry = format!(
+            "  {swatch} {} +{} -{}",
+            counts.live, counts.born, counts.died
+        );
+        legend.push_str(&entry);
+        if legend.chars().count() > width {
+            break;
+        }
+
+        let style = theme.marked_style(color as usize, Intensity::Bold);
+        queue!(stdout, PrintStyledContent(style.apply(entry)))?;
+    }
+
+    // Graph
+    for row in 0..rows {
+        queue!(stdout, MoveTo(0, (top + 1 + row) as u16))?;
+
+        for (bits, colors) in &dots[row * width..][..width] {
+            let Some(&(color, _)) = colors.iter().max_by_key(|(_, count)| count) else {
+                queue!(
+                    stdout,
+                    PrintStyledContent(ContentStyle::default().apply(' '))
+                )?;
+                continue;
+            };
+
+            let glyph = match charset {
+                Charset::Unicode => char::from_u32(0x2800 + *bits as u32).unwrap(),
+                Charset::Ascii => '*',
+            };
+            let style = theme.marked_style(color as usize, Intensity::Bold);
+            queue!(stdout, PrintStyledContent(style.apply(glyph)))?;
+        }
+    }
+
+    stdout.flush()
+}
+
+/// Returns the size of board that fills a terminal of the given size.
+///
+/// Hexagonal boards take two columns per cell (see [`render`]), and have an
+/// even number of rows so that the offset rows line up when they wrap around.
+/// A terminal one row high still gets a hexagonal board of two rows.
+pub fn board_size(width: usize, height: usize, neighborhood: Neighborhood) -> (usize, usize) {
+    if neighborhood.is_hexagonal() {
+        (width.saturating_sub(1) / 2, (height - height % 2).max(2))
+    } else {
+        (width, height)
+    }
+}
+
+/// Returns the column of the glyph ramp used for a dead cell of the given age.
+///
+/// Dead cells normally move one column along the ramp per generation. Under
+/// Generations rules with `states` states, the refractory states are instead
+/// spread evenly from the second column to the last, so that the whole ramp is
+/// used however many states there are.
+fn ramp_column(age: u32, states: u32, len: usize) -> usize {
+    let age = age as usize;
+
+    match states.saturating_sub(2) as usize {
+        0 => (age - 1).min(len - 1),
+        1 => 1,
+        refractory => 1 + (age - 1).min(refractory - 1) * (len - 2) / (refractory - 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hexagonal_boards_have_an_even_number_of_rows() {
+        let hexagonal = Neighborhood::Hexagonal(1);
+        assert_eq!(board_size(81, 25, hexagonal), (40, 24));
+        assert_eq!(board_size(81, 24, hexagonal), (40, 24));
+        assert_eq!(board_size(81, 1, hexagonal), (40, 2));
+        assert_eq!(board_size(81, 1, Neighborhood::Moore(1)), (81, 1));
+    }
+}
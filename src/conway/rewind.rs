@@ -0,0 +1,120 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use std::{collections::VecDeque, io::Result as IoResult};
+
+use crossterm::event::KeyCode;
+
+use crate::{engine::Engine, renderer::render_message};
+
+/// Default number of earlier generations kept for rewinding.
+pub const DEFAULT_REWIND: usize = 100;
+
+/// Keys handled while rewinding, in addition to those that exit.
+pub const KEYS: [KeyCode; 7] = [
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::Char('b'),
+    KeyCode::Char('f'),
+    KeyCode::Enter,
+];
+
+/// A bounded history of the states an engine has passed through, so that the
+/// simulation can be stepped backwards and resumed from an earlier generation.
+///
+/// A snapshot (see [`Engine::snapshot`]) is saved before each change to the
+/// engine, so the history holds the generations before the current one, most
+/// recent last. Once it is full, the oldest snapshot is discarded for each one
+/// saved.
+pub struct Rewind {
+    snapshots: VecDeque<Box<dyn Engine>>,
+    capacity: usize,
+}
+
+impl Rewind {
+    /// Creates an empty history that keeps up to `capacity` snapshots, or
+    /// none if it is 0.
+    pub fn new(capacity: usize) -> Self {
+        Rewind {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns the number of snapshots kept.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Returns `true` if there is nothing to rewind to.
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Saves a snapshot of the engine's current state, before it changes.
+    pub fn save(&mut self, engine: &dyn Engine) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(engine.snapshot());
+    }
+
+    /// Returns the snapshot taken the given number of saves ago, where 1 is
+    /// the most recent, or `None` if it is not kept.
+    pub fn get(&self, back: usize) -> Option<&dyn Engine> {
+        let index = self.snapshots.len().checked_sub(back)?;
+        self.snapshots.get(index).map(|snapshot| snapshot.as_ref())
+    }
+
+    /// Removes and returns the snapshot taken the given number of saves ago,
+    /// to resume the simulation from. The snapshots taken after it are
+    /// discarded, as the simulation continues from there instead.
+    ///
+    /// Returns `None`, and keeps every snapshot, if `back` is 0 or the
+    /// snapshot is not kept.
+    pub fn restore(&mut self, back: usize) -> Option<Box<dyn Engine>> {
+        let index = self
+            .snapshots
+            .len()
+            .checked_sub(back)
+            .filter(|_| back > 0)?;
+        self.snapshots.truncate(index + 1);
+        self.snapshots.pop_back()
+    }
+
+    /// Discards every snapshot, when the board is reset.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /// Renders the status line shown while rewinding.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `generation` - The generation shown.
+    /// - `back` - The number of saves ago that the generation shown was saved,
+    ///   or 0 for the current generation.
+    /// - `columns` - The width of the terminal.
+    /// - `rows` - The height of the terminal.
+    pub fn render(
+        &self,
+        generation: usize,
+        back: usize,
+        columns: usize,
+        rows: usize,
+    ) -> IoResult<()> {
+        let status = format!(
+            "REWIND gen {generation} ({back} of {} back) | left/right step, home/end jump, \
+             enter resumes",
+            self.len()
+        );
+        render_message(&status, columns, rows)
+    }
+}
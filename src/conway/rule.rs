@@ -0,0 +1,523 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use std::{fmt, str::FromStr};
+
+use crate::{inheritance::Inheritance, neighborhood::Neighborhood};
+
+/// Well-known rules that may be given by name, with their rulestrings.
+///
+/// Names are matched case-insensitively, ignoring spaces and punctuation, so
+/// "Day & Night" and "daynight" are the same rule.
+pub const NAMED_RULES: [(&str, &str); 15] = [
+    ("Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Seeds", "B2/S"),
+    ("Day & Night", "B3678/S34678"),
+    ("Life without Death", "B3/S012345678"),
+    ("2x2", "B36/S125"),
+    ("Replicator", "B1357/S1357"),
+    ("Diamoeba", "B35678/S5678"),
+    ("Morley", "B368/S245"),
+    ("Maze", "B3/S12345"),
+    ("Brian's Brain", "B2/S/C3"),
+    ("Star Wars", "B2/S345/C4"),
+    ("Hexagonal Life", "B2/S34H"),
+    ("Bosco's Rule", "R5,C0,M1,S34..58,B34..45,NM"),
+    ("Majority", "R4,C0,M1,S41..81,B41..81,NM"),
+];
+
+/// Largest range of Larger than Life neighbourhoods, as in Golly, beyond which
+/// their offsets would take too much memory and time to visit.
+pub const MAX_RANGE: usize = 500;
+
+/// A Life-like cellular automaton rule.
+///
+/// Rules are written in B/S notation, listing the numbers of living neighbours
+/// that cause a dead cell to be born and a living cell to survive. Conway's
+/// Game of Life is `B3/S23`: a cell is born with exactly three neighbours and
+/// survives with two or three.
+///
+/// With coloured cells, survival counts only neighbours of the cell's own
+/// colour, while [`Rule::overpopulated`] counts neighbours of any colour (see
+/// [`crate::board::Cell::next`]).
+///
+/// "Generations" rules such as Brian's Brain (`B2/S/C3`) add a number of
+/// states. A living cell that does not survive passes through the refractory
+/// states in turn before it is dead, and cannot be born again until then.
+/// Life-like rules have two states, alive and dead, and no refractory states.
+///
+/// Rules may also use other [`Neighborhood`]s. The B/S notation takes an `H`
+/// suffix for the hexagonal neighbourhood or a `V` suffix for the von Neumann
+/// neighbourhood, while Larger than Life rules such as Bosco's Rule
+/// (`R5,C0,M1,S34..58,B34..45,NM`) give the range and shape of their
+/// neighbourhood along with ranges of counts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    birth: Vec<bool>,
+    survival: Vec<bool>,
+    states: u32,
+    neighborhood: Neighborhood,
+    include_center: bool,
+    inheritance: Inheritance,
+}
+
+impl Rule {
+    /// Creates a rule in which no cells are born or survive.
+    fn empty(neighborhood: Neighborhood, include_center: bool, states: u32) -> Self {
+        let counts = neighborhood.size() + 1 + include_center as usize;
+        Rule {
+            birth: vec![false; counts],
+            survival: vec![false; counts],
+            states,
+            neighborhood,
+            include_center,
+            inheritance: Inheritance::default(),
+        }
+    }
+
+    /// Creates the rule for Conway's Game of Life (`B3/S23`).
+    pub fn life() -> Self {
+        let mut rule = Rule::empty(Neighborhood::Moore(1), false, 2);
+        rule.birth[3] = true;
+        rule.survival[2] = true;
+        rule.survival[3] = true;
+        rule
+    }
+
+    /// Returns a copy of this rule in which newborn cells take their colour
+    /// according to the given policy.
+    ///
+    /// The policy is not part of the rule's notation, so it is not saved with
+    /// patterns.
+    pub fn with_inheritance(self, inheritance: Inheritance) -> Self {
+        Rule {
+            inheritance,
+            ..self
+        }
+    }
+
+    /// Returns `true` if a dead cell with `count` living neighbours is born.
+    pub fn born(&self, count: usize) -> bool {
+        self.birth.get(count).copied().unwrap_or(false)
+    }
+
+    /// Returns `true` if a living cell with `count` living neighbours
+    /// survives.
+    ///
+    /// Rules whose counts include the cell itself (`M1` in Larger than Life
+    /// notation) add one to `count` before checking it.
+    pub fn survives(&self, count: usize) -> bool {
+        let count = count + self.include_center as usize;
+        self.survival.get(count).copied().unwrap_or(false)
+    }
+
+    /// Returns `true` if a living cell with `count` living neighbours of any
+    /// colour dies of overpopulation, which happens when `count` is more than
+    /// the largest survival count.
+    pub fn overpopulated(&self, count: usize) -> bool {
+        let count = count + self.include_center as usize;
+        match self.survival.iter().rposition(|&survives| survives) {
+            Some(max) => count > max,
+            None => true,
+        }
+    }
+
+    /// Returns the number of states a cell may be in, including the living and
+    /// dead states.
+    pub fn states(&self) -> u32 {
+        self.states
+    }
+
+    /// Returns `true` if a cell that died `age` generations ago is still in a
+    /// refractory state, in which it cannot be born.
+    pub fn refractory(&self, age: u32) -> bool {
+        age > 0 && age + 2 <= self.states
+    }
+
+    /// Returns the neighbourhood in which living neighbours are counted.
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    /// Returns how newborn cells take their colour from their parents.
+    pub fn inheritance(&self) -> Inheritance {
+        self.inheritance
+    }
+
+    /// Parses a Larger than Life rule such as `R5,C0,M1,S34..58,B34..45,NM`.
+    ///
+    /// The fields are the range (`R`, up to [`MAX_RANGE`]), the number of states (`C`, where 0
+    /// means 2), whether the cell counts itself (`M`), the survival and birth
+    /// counts (`S` and `B`) and the neighbourhood (`N`), which may be `M` for
+    /// Moore, `N` for von Neumann or `H` for hexagonal. Counts are given as
+    /// `a..b` or `a-b` ranges or as single numbers, and several may be given
+    /// separated by commas.
+    fn from_ltl(s: &str) -> Result<Self, String> {
+        let mut range = 1;
+        let mut states = 2;
+        let mut include_center = false;
+        let mut shape = 'M';
+        let mut birth = Vec::new();
+        let mut survival = Vec::new();
+        let mut counts: Option<&mut Vec<(usize, usize)>> = None;
+
+        for field in s.split(',').map(str::trim) {
+            let (key, value) = match field.chars().next() {
+                Some(ch) if ch.is_ascii_alphabetic() => (ch.to_ascii_uppercase(), &field[1..]),
+                _ => (' ', field),
+            };
+            let number = || {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| format!("'{field}' is not a valid rule field."))
+            };
+
+            match key {
+                'R' => range = number()?,
+                'C' => states = number()?.max(2) as u32,
+                'M' => include_center = number()? != 0,
+                'N' => shape = value.chars().next().unwrap_or('M').to_ascii_uppercase(),
+                'S' => counts = Some(&mut survival),
+                'B' => counts = Some(&mut birth),
+                // Further counts for the preceding `S` or `B` field.
+                ' ' if counts.is_some() => {}
+                _ => return Err(format!("'{field}' is not a valid rule field.")),
+            }
+
+            if !matches!(key, 'S' | 'B' | ' ') {
+                counts = None;
+            } else if let Some(counts) = counts.as_mut()
+                && !value.is_empty()
+            {
+                counts.push(parse_count_range(value)?);
+            }
+        }
+
+        if !(1..=MAX_RANGE).contains(&range) {
+            return Err(format!("The range must be from 1 to {MAX_RANGE}."));
+        }
+
+        let neighborhood = match shape {
+            'M' => Neighborhood::Moore(range),
+            'N' => Neighborhood::VonNeumann(range),
+            'H' => Neighborhood::Hexagonal(range),
+            other => return Err(format!("'N{other}' is not a valid neighbourhood.")),
+        };
+
+        let mut rule = Rule::empty(neighborhood, include_center, states);
+        let max = rule.birth.len() - 1;
+        for (ranges, table) in [(birth, &mut rule.birth), (survival, &mut rule.survival)] {
+            for (low, high) in ranges {
+                if high > max {
+                    return Err(format!(
+                        "{high} is more than the {max} possible neighbours."
+                    ));
+                }
+                table[low..=high].fill(true);
+            }
+        }
+
+        Ok(rule)
+    }
+
+    /// Formats the rule in Larger than Life notation.
+    fn fmt_ltl(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let states = if self.states > 2 { self.states } else { 0 };
+        write!(
+            f,
+            "R{},C{states},M{}",
+            self.neighborhood.range(),
+            self.include_center as u8
+        )?;
+
+        for (key, table) in [('S', &self.survival), ('B', &self.birth)] {
+            write!(f, ",{key}")?;
+
+            let mut n = 0;
+            let mut first = true;
+            while n < table.len() {
+                if !table[n] {
+                    n += 1;
+                    continue;
+                }
+
+                if !first {
+                    write!(f, ",")?;
+                }
+                let run = table[n..].iter().take_while(|&&set| set).count();
+                match run {
+                    1 => write!(f, "{n}")?,
+                    _ => write!(f, "{n}..{}", n + run - 1)?,
+                }
+
+                first = false;
+                n += run;
+            }
+        }
+
+        let shape = match self.neighborhood {
+            Neighborhood::Moore(_) => 'M',
+            Neighborhood::VonNeumann(_) => 'N',
+            Neighborhood::Hexagonal(_) => 'H',
+        };
+        write!(f, ",N{shape}")
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::life()
+    }
+}
+
+/// Parses a rule in B/S notation (such as `B3/S23`), the older S/B notation
+/// (such as `23/3`), Larger than Life notation (see [`Rule::from_ltl`]) or by
+/// name (see [`NAMED_RULES`]). Letters are case-insensitive and the slash is
+/// optional when the `B` and `S` prefixes are given.
+///
+/// Generations rules add the number of states, as in `B2/S/C3` or `/2/3`, and
+/// B/S rules may end with `H` or `V` to use the hexagonal or von Neumann
+/// neighbourhoods.
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let name = normalize_name(s);
+        if let Some((_, rulestring)) = NAMED_RULES
+            .iter()
+            .find(|(named, _)| normalize_name(named) == name)
+        {
+            return Rule::from_str(rulestring);
+        }
+
+        if s.starts_with(['R', 'r']) {
+            return Rule::from_ltl(s);
+        }
+
+        let (s, neighborhood) = match s.chars().last() {
+            Some('H' | 'h') => (&s[..s.len() - 1], Neighborhood::Hexagonal(1)),
+            Some('V' | 'v') => (&s[..s.len() - 1], Neighborhood::VonNeumann(1)),
+            _ => (s, Neighborhood::Moore(1)),
+        };
+
+        if !s.starts_with(['B', 'b', 'S', 's']) {
+            // S/B notation, with survival counts first.
+            let mut parts = s.split('/');
+            let rulestring = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(survival), Some(birth), None, _) => format!("B{birth}/S{survival}"),
+                (Some(survival), Some(birth), Some(states), None) => {
+                    format!("B{birth}/S{survival}/C{states}")
+                }
+                _ => return Err(format!("'{s}' is not a rule name or B/S rulestring.")),
+            };
+            return parse_bs(&rulestring, neighborhood);
+        }
+
+        parse_bs(s, neighborhood)
+    }
+}
+
+/// Formats the rule in B/S notation, or B/S/C notation for Generations rules,
+/// followed by the neighbourhood's suffix if it is not the Moore
+/// neighbourhood. Larger than Life rules are formatted in their own notation.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.neighborhood.range() > 1 || self.include_center {
+            return self.fmt_ltl(f);
+        }
+
+        write!(f, "B")?;
+        for n in (0..self.birth.len()).filter(|&n| self.birth[n]) {
+            write!(f, "{n}")?;
+        }
+        write!(f, "/S")?;
+        for n in (0..self.survival.len()).filter(|&n| self.survival[n]) {
+            write!(f, "{n}")?;
+        }
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+
+        match self.neighborhood {
+            Neighborhood::Moore(_) => Ok(()),
+            Neighborhood::VonNeumann(_) => write!(f, "V"),
+            Neighborhood::Hexagonal(_) => write!(f, "H"),
+        }
+    }
+}
+
+/// Parses a rule in B/S or B/S/C notation with the given neighbourhood.
+fn parse_bs(s: &str, neighborhood: Neighborhood) -> Result<Rule, String> {
+    // Generations rules end with the number of states.
+    let (s, states) = match s.find(['C', 'c', 'G', 'g']) {
+        Some(i) => {
+            let states = s[i + 1..]
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("'{}' is not a valid number of states.", &s[i + 1..]))?;
+            (s[..i].trim_end_matches('/'), states)
+        }
+        None => (s, 2),
+    };
+    if states < 2 {
+        return Err(String::from("Rules must have at least 2 states."));
+    }
+
+    let mut rule = Rule::empty(neighborhood, false, states);
+    let max = neighborhood.size();
+
+    let mut counts = None;
+    for ch in s.chars() {
+        match ch {
+            'B' | 'b' => counts = Some(&mut rule.birth),
+            'S' | 's' => counts = Some(&mut rule.survival),
+            '/' => {}
+            _ => match (ch.to_digit(10), counts.as_mut()) {
+                (Some(n), Some(counts)) if (n as usize) <= max => {
+                    counts[n as usize] = true;
+                }
+                _ => return Err(format!("'{ch}' is not a valid neighbour count.")),
+            },
+        }
+    }
+
+    Ok(rule)
+}
+
+/// Parses a range of neighbour counts written as `a..b`, `a-b` or `a`.
+fn parse_count_range(s: &str) -> Result<(usize, usize), String> {
+    let parse = |n: &str| {
+        n.trim()
+            .parse::<usize>()
+            .map_err(|_| format!("'{s}' is not a valid range of neighbour counts."))
+    };
+
+    let (low, high) = match s.split_once("..").or_else(|| s.split_once('-')) {
+        Some((low, high)) => (parse(low)?, parse(high)?),
+        None => (parse(s)?, parse(s)?),
+    };
+
+    if low > high {
+        return Err(format!("'{s}' is not a valid range of neighbour counts."));
+    }
+    Ok((low, high))
+}
+
+/// Lowercases a rule or pattern name and strips everything but letters and
+/// digits, so that names can be compared loosely.
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|ch| ch.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_rules() {
+        let cases = [
+            ("B3/S23", "B3/S23"),
+            ("b3/s23", "B3/S23"),
+            ("B3S23", "B3/S23"),
+            ("S23/B3", "B3/S23"),
+            ("23/3", "B3/S23"),
+            ("B36/S23", "B36/S23"),
+            ("B2/S", "B2/S"),
+            ("B2/S/C3", "B2/S/C3"),
+            ("B2/S345/C4", "B2/S345/C4"),
+            ("B2/S345G4", "B2/S345/C4"),
+            ("345/2/4", "B2/S345/C4"),
+            ("B2/S34H", "B2/S34H"),
+            ("B2/S34h", "B2/S34H"),
+            ("B1/S1V", "B1/S1V"),
+            ("Life", "B3/S23"),
+            ("day & night", "B3678/S34678"),
+            ("Brian's Brain", "B2/S/C3"),
+            ("R5,C0,M1,S34..58,B34..45,NM", "R5,C0,M1,S34..58,B34..45,NM"),
+            ("r2,c3,m0,s2-3,b3,nn", "R2,C3,M0,S2..3,B3,NN"),
+            ("R2,C0,M0,S2,4,6..8,B3,NH", "R2,C0,M0,S2,4,6..8,B3,NH"),
+            ("R1,C0,M1,S3..4,B3,NM", "R1,C0,M1,S3..4,B3,NM"),
+        ];
+
+        for (input, expected) in cases {
+            let rule = input.parse::<Rule>();
+            assert_eq!(
+                rule.map(|rule| rule.to_string()),
+                Ok(expected.to_string()),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_neighbourhoods_and_states() {
+        let cases = [
+            ("B3/S23", Neighborhood::Moore(1), 2),
+            ("B2/S34H", Neighborhood::Hexagonal(1), 2),
+            ("B1/S1V", Neighborhood::VonNeumann(1), 2),
+            ("B2/S/C3", Neighborhood::Moore(1), 3),
+            ("R5,C0,M1,S34..58,B34..45,NM", Neighborhood::Moore(5), 2),
+            ("R3,C5,M0,S2,B3,NN", Neighborhood::VonNeumann(3), 5),
+        ];
+
+        for (input, neighborhood, states) in cases {
+            let rule = input.parse::<Rule>().unwrap();
+            assert_eq!(rule.neighborhood(), neighborhood, "{input}");
+            assert_eq!(rule.states(), states, "{input}");
+        }
+    }
+
+    #[test]
+    fn named_rules_round_trip_through_display() {
+        for (name, rulestring) in NAMED_RULES {
+            let rule = name.parse::<Rule>().unwrap();
+            assert_eq!(rule, rulestring.parse().unwrap(), "{name}");
+            assert_eq!(rule.to_string().parse::<Rule>(), Ok(rule), "{name}");
+        }
+    }
+
+    #[test]
+    fn applies_birth_and_survival_counts() {
+        let life = Rule::life();
+        assert!(life.born(3) && !life.born(2) && !life.born(4));
+        assert!(life.survives(2) && life.survives(3) && !life.survives(4));
+        assert!(life.overpopulated(4) && !life.overpopulated(3));
+
+        // Counts include the cell itself with M1.
+        let rule = "R1,C0,M1,S3..4,B3,NM".parse::<Rule>().unwrap();
+        assert!(rule.survives(2) && rule.survives(3) && !rule.survives(1));
+
+        let brain = "B2/S/C3".parse::<Rule>().unwrap();
+        assert!(brain.refractory(1) && !brain.refractory(2) && !brain.refractory(0));
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let cases = [
+            "",
+            "xyz",
+            "B9/S23",
+            "B3/S23/C1",
+            "B3/S23/Cx",
+            "B5/S2V",
+            "1/2/3/4",
+            "R0,C0,M0,S1,B1,NM",
+            "R501,C0,M0,S1,B1,NM",
+            "R18446744073709551615,C0,M0,S1,B1,NM",
+            "R1,C0,M0,S9,B1,NM",
+            "R1,C0,M0,S3..2,B1,NM",
+            "R1,C0,M0,S1,B1,NX",
+            "R1,Q0",
+        ];
+
+        for input in cases {
+            assert!(input.parse::<Rule>().is_err(), "{input}");
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+//! Random "soups" of living cells, from which the simulation starts when no
+//! pattern is given.
+
+use clap::ValueEnum;
+use rand::{
+    Rng,
+    distr::{Bernoulli, Distribution, Uniform},
+};
+
+use crate::{board::Cell, pattern::Pattern};
+
+/// Default probability that each cell of a soup is alive.
+pub const DEFAULT_DENSITY: f64 = 0.33;
+
+/// The shape of the region filled by a soup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Shape {
+    /// The whole of the soup's bounding box is filled.
+    #[default]
+    Rectangle,
+
+    /// Only the circle, or ellipse, that fits inside the soup's bounding box
+    /// is filled.
+    Circle,
+}
+
+/// The symmetry of a soup, in the notation used by Catagolue.
+///
+/// Symmetric soups tend to produce symmetric, and often larger, patterns than
+/// asymmetric ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Symmetry {
+    /// No symmetry.
+    #[default]
+    #[value(name = "none", alias = "c1")]
+    C1,
+
+    /// The soup is unchanged by a half turn.
+    C2,
+
+    /// The soup is unchanged by a quarter turn. The soup is made square.
+    C4,
+
+    /// The soup is unchanged by mirroring from left to right or from top to
+    /// bottom.
+    D4,
+
+    /// The soup is unchanged by any quarter turn or mirroring. The soup is
+    /// made square.
+    D8,
+}
+
+impl Symmetry {
+    /// Returns whether the symmetry includes quarter turns, which only map a
+    /// region onto itself if it is square.
+    fn is_square(self) -> bool {
+        matches!(self, Symmetry::C4 | Symmetry::D8)
+    }
+
+    /// Returns the cells that the given cell is mapped to by the symmetry,
+    /// including itself, in a region of the given size.
+    fn orbit(self, x: usize, y: usize, width: usize, height: usize) -> Vec<(usize, usize)> {
+        let (flip_x, flip_y) = (width - 1 - x, height - 1 - y);
+        match self {
+            Symmetry::C1 => vec![(x, y)],
+            Symmetry::C2 => vec![(x, y), (flip_x, flip_y)],
+            Symmetry::C4 => vec![(x, y), (flip_y, x), (flip_x, flip_y), (y, flip_x)],
+            Symmetry::D4 => vec![(x, y), (flip_x, y), (x, flip_y), (flip_x, flip_y)],
+            Symmetry::D8 => vec![
+                (x, y),
+                (flip_y, x),
+                (flip_x, flip_y),
+                (y, flip_x),
+                (flip_x, y),
+                (x, flip_y),
+                (y, x),
+                (flip_y, flip_x),
+            ],
+        }
+    }
+}
+
+/// A generator of random soups of living cells.
+///
+/// Each cell of the soup's region is alive with the given density, and given a
+/// colour chosen uniformly from those passed to [`Soup::generate`]. The
+/// generator draws all of its randomness from the random number generator it
+/// is given, so a seeded generator always produces the same soup.
+#[derive(Clone, Copy, Debug)]
+pub struct Soup {
+    width: usize,
+    height: usize,
+    density: f64,
+    shape: Shape,
+    symmetry: Symmetry,
+}
+
+impl Soup {
+    /// Creates a generator of rectangular, asymmetric soups of the given size
+    /// with the default density.
+    pub fn new(width: usize, height: usize) -> Self {
+        Soup {
+            width,
+            height,
+            density: DEFAULT_DENSITY,
+            shape: Shape::default(),
+            symmetry: Symmetry::default(),
+        }
+    }
+
+    /// Returns a copy of this generator whose cells are alive with the given
+    /// probability, which is clamped between 0 and 1.
+    pub fn with_density(self, density: f64) -> Self {
+        Soup {
+            density: density.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Returns a copy of this generator that fills a region of the given
+    /// shape.
+    pub fn with_shape(self, shape: Shape) -> Self {
+        Soup { shape, ..self }
+    }
+
+    /// Returns a copy of this generator that makes soups with the given
+    /// symmetry.
+    pub fn with_symmetry(self, symmetry: Symmetry) -> Self {
+        Soup { symmetry, ..self }
+    }
+
+    /// Generates a soup.
+    ///
+    /// Arguments
+    /// =========
+    ///
+    /// - `rand` - The random number generator to draw from.
+    /// - `colors` - The colours that living cells may have, which must not be
+    ///   empty.
+    ///
+    /// Returns
+    /// =======
+    ///
+    /// A pattern the size of the soup, or of the largest square that fits in
+    /// it if the symmetry includes quarter turns.
+    pub fn generate<R: Rng>(&self, rand: &mut R, colors: &[u32]) -> Pattern {
+        let (width, height) = match self.symmetry.is_square() {
+            true => (self.width.min(self.height), self.width.min(self.height)),
+            false => (self.width, self.height),
+        };
+
+        let spawn = Bernoulli::new(self.density).unwrap();
+        let color = Uniform::new(0, colors.len()).unwrap();
+
+        // Each cell is decided once, together with the other cells it is
+        // mapped to by the symmetry.
+        let mut decided = vec![false; width * height];
+        let mut pattern = Pattern::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                if decided[y * width + x] {
+                    continue;
+                }
+
+                let cell = match spawn.sample(rand) {
+                    true => Cell::new(colors[color.sample(rand)]),
+                    false => Cell::empty(),
+                };
+                for (x, y) in self.symmetry.orbit(x, y, width, height) {
+                    if !decided[y * width + x] && self.contains(x, y, width, height) {
+                        pattern.set(x, y, cell);
+                    }
+                    decided[y * width + x] = true;
+                }
+            }
+        }
+        pattern
+    }
+
+    /// Returns whether the cell at the given coordinates lies within the
+    /// soup's shape, in a region of the given size.
+    fn contains(&self, x: usize, y: usize, width: usize, height: usize) -> bool {
+        match self.shape {
+            Shape::Rectangle => true,
+            Shape::Circle => {
+                // Measure from the cells' centres, as a fraction of the radii.
+                let dx = (2.0 * x as f64 + 1.0 - width as f64) / width as f64;
+                let dy = (2.0 * y as f64 + 1.0 - height as f64) / height as f64;
+                dx * dx + dy * dy <= 1.0
+            }
+        }
+    }
+}
+
+/// Parses a soup density, which must be between 0 and 1.
+pub fn parse_density(s: &str) -> Result<f64, String> {
+    match s.trim().parse() {
+        Ok(density) if (0.0..=1.0).contains(&density) => Ok(density),
+        _ => Err(format!("'{s}' is not a probability between 0 and 1.")),
+    }
+}
+
+/// Parses a soup size written as `WIDTHxHEIGHT`, such as `40x20`.
+pub fn parse_size(s: &str) -> Result<(usize, usize), String> {
+    let parsed = s.split_once(['x', 'X']).and_then(|(width, height)| {
+        Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+    });
+    match parsed {
+        Some((width, height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(format!("'{s}' is not a size such as 40x20.")),
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use clap::ValueEnum;
+
+/// How the edges of a board are joined together.
+///
+/// Cells beyond an edge that is not joined to another are always dead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Topology {
+    /// Both pairs of opposite edges are joined, so that cells leaving one edge
+    /// reappear at the opposite edge.
+    #[default]
+    Torus,
+
+    /// No edges are joined.
+    Bounded,
+
+    /// The left and right edges are joined; the top and bottom are not.
+    Cylinder,
+
+    /// The left and right edges are joined, and the top and bottom edges are
+    /// joined with a twist, so that cells leaving the top reappear at the
+    /// bottom mirrored left to right.
+    KleinBottle,
+
+    /// Both pairs of opposite edges are joined with a twist.
+    ProjectivePlane,
+}
+
+impl Topology {
+    /// Finds the cell at the given coordinates, which may lie beyond the edges
+    /// of a board of the given size.
+    ///
+    /// Returns `None` if the coordinates lie beyond an edge that is not joined
+    /// to another.
+    pub fn locate(self, x: isize, y: isize, width: usize, height: usize) -> Option<(usize, usize)> {
+        let (wrapped_x, twist_y) = wrap(x, width);
+        let (wrapped_y, twist_x) = wrap(y, height);
+        let inside_x = (0..width as isize).contains(&x);
+        let inside_y = (0..height as isize).contains(&y);
+
+        match self {
+            Topology::Torus => Some((wrapped_x, wrapped_y)),
+            Topology::Bounded => (inside_x && inside_y).then_some((x as usize, y as usize)),
+            Topology::Cylinder => inside_y.then_some((wrapped_x, y as usize)),
+            Topology::KleinBottle => Some((mirror(wrapped_x, width, twist_x), wrapped_y)),
+            Topology::ProjectivePlane => Some((
+                mirror(wrapped_x, width, twist_x),
+                mirror(wrapped_y, height, twist_y),
+            )),
+        }
+    }
+
+    /// Returns the topology's description in the suffix Golly adds to rules in
+    /// RLE files, such as `T80,40` for an 80 by 40 torus.
+    pub fn to_golly(self, width: usize, height: usize) -> String {
+        match self {
+            Topology::Torus => format!("T{width},{height}"),
+            Topology::Bounded => format!("P{width},{height}"),
+            Topology::Cylinder => format!("T{width},0"),
+            Topology::KleinBottle => format!("K{width}*,{height}"),
+            Topology::ProjectivePlane => format!("C{width},{height}"),
+        }
+    }
+
+    /// Parses the suffix Golly adds to rules in RLE files (see
+    /// [`Topology::to_golly`]), along with the width and height of the board
+    /// it describes. A torus with a height of 0 is a cylinder, which is
+    /// unbounded vertically, so its height is 0.
+    ///
+    /// Returns `None` if the suffix does not describe a supported topology,
+    /// including tori with a width of 0 or shifted edges (such as
+    /// `T80+2,40`), and Klein bottles twisted on their left and right edges
+    /// (`K80,40*`).
+    pub fn from_golly(s: &str) -> Option<(Self, usize, usize)> {
+        let s = s.trim();
+        let mut chars = s.chars();
+        let kind = chars.next()?.to_ascii_uppercase();
+        let (width, height) = chars.as_str().split_once(',')?;
+        let (width, width_twist) = parse_dimension(width)?;
+        let (height, height_twist) = parse_dimension(height)?;
+
+        if width == 0 {
+            return None;
+        }
+
+        let topology = match (kind, width_twist, height_twist) {
+            ('T', false, false) if height == 0 => Topology::Cylinder,
+            _ if height == 0 => return None,
+            ('T', false, false) => Topology::Torus,
+            ('P', false, false) => Topology::Bounded,
+            ('K', true, false) => Topology::KleinBottle,
+            ('C', false, false) => Topology::ProjectivePlane,
+            _ => return None,
+        };
+        Some((topology, width, height))
+    }
+}
+
+/// Parses one dimension of a Golly topology suffix, a number optionally
+/// followed by `*` to mark the edges it joins as twisted.
+///
+/// Returns `None` for anything else, such as a shift (`80+2`).
+fn parse_dimension(s: &str) -> Option<(usize, bool)> {
+    let s = s.trim();
+    let (number, twist) = match s.strip_suffix('*') {
+        Some(number) => (number, true),
+        None => (s, false),
+    };
+
+    if number.is_empty() || !number.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    Some((number.parse().ok()?, twist))
+}
+
+/// Wraps a coordinate onto an axis of the given length, and returns whether it
+/// crossed the edges an odd number of times.
+fn wrap(v: isize, len: usize) -> (usize, bool) {
+    let len = len as isize;
+    (v.rem_euclid(len) as usize, v.div_euclid(len) % 2 != 0)
+}
+
+/// Mirrors a coordinate on an axis of the given length if `twist` is set.
+fn mirror(v: usize, len: usize, twist: bool) -> usize {
+    if twist { len - 1 - v } else { v }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_golly_suffixes_with_their_dimensions() {
+        let cases = [
+            ("T80,40", Some((Topology::Torus, 80, 40))),
+            ("t80,40", Some((Topology::Torus, 80, 40))),
+            ("P30,20", Some((Topology::Bounded, 30, 20))),
+            ("T80,0", Some((Topology::Cylinder, 80, 0))),
+            ("K80*,40", Some((Topology::KleinBottle, 80, 40))),
+            (" C12,10 ", Some((Topology::ProjectivePlane, 12, 10))),
+            ("K80,40*", None),
+            ("T80+2,40", None),
+            ("T0,40", None),
+            ("P30,0", None),
+            ("S80,40", None),
+            ("T80", None),
+            ("T-1,40", None),
+        ];
+
+        for (suffix, expected) in cases {
+            assert_eq!(Topology::from_golly(suffix), expected, "{suffix}");
+        }
+    }
+
+    #[test]
+    fn golly_suffixes_round_trip() {
+        for topology in Topology::value_variants() {
+            let suffix = topology.to_golly(80, 40);
+            let height = if *topology == Topology::Cylinder {
+                0
+            } else {
+                40
+            };
+            assert_eq!(Topology::from_golly(&suffix), Some((*topology, 80, height)));
+        }
+    }
+
+    #[test]
+    fn torus_wraps_both_edges() {
+        let torus = Topology::Torus;
+        assert_eq!(torus.locate(-1, 0, 4, 3), Some((3, 0)));
+        assert_eq!(torus.locate(4, 2, 4, 3), Some((0, 2)));
+        assert_eq!(torus.locate(1, -1, 4, 3), Some((1, 2)));
+        assert_eq!(torus.locate(-1, 3, 4, 3), Some((3, 0)));
+    }
+
+    #[test]
+    fn bounded_and_cylinder_edges_are_dead() {
+        assert_eq!(Topology::Bounded.locate(2, 1, 4, 3), Some((2, 1)));
+        assert_eq!(Topology::Bounded.locate(-1, 1, 4, 3), None);
+        assert_eq!(Topology::Bounded.locate(2, 3, 4, 3), None);
+        assert_eq!(Topology::Cylinder.locate(-1, 1, 4, 3), Some((3, 1)));
+        assert_eq!(Topology::Cylinder.locate(1, -1, 4, 3), None);
+    }
+
+    #[test]
+    fn klein_bottle_twists_the_top_and_bottom_edges() {
+        let klein = Topology::KleinBottle;
+        // Left and right are joined plainly.
+        assert_eq!(klein.locate(-1, 1, 4, 3), Some((3, 1)));
+        assert_eq!(klein.locate(4, 1, 4, 3), Some((0, 1)));
+        // Top and bottom are joined mirrored left to right.
+        assert_eq!(klein.locate(0, -1, 4, 3), Some((3, 2)));
+        assert_eq!(klein.locate(1, 3, 4, 3), Some((2, 0)));
+        assert_eq!(klein.locate(1, 6, 4, 3), Some((1, 0)));
+    }
+
+    #[test]
+    fn projective_plane_twists_both_pairs_of_edges() {
+        let cross = Topology::ProjectivePlane;
+        assert_eq!(cross.locate(-1, 0, 4, 3), Some((3, 2)));
+        assert_eq!(cross.locate(4, 2, 4, 3), Some((0, 0)));
+        assert_eq!(cross.locate(0, -1, 4, 3), Some((3, 2)));
+        assert_eq!(cross.locate(1, 3, 4, 3), Some((2, 0)));
+        assert_eq!(cross.locate(-1, -1, 4, 3), Some((0, 0)));
+        assert_eq!(cross.locate(2, 1, 4, 3), Some((2, 1)));
+    }
+}
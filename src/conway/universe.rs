@@ -0,0 +1,217 @@
+// Copyright (c) 2025 Jonathon Burnham Cobb
+// Licensed under the MIT-0 license.
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, RandomState},
+};
+
+use crate::{
+    board::{Board, Cell},
+    census::Census,
+    cycle::{Cycle, CycleDetector},
+    engine::{Engine, Viewport},
+    pattern::Pattern,
+    rule::Rule,
+};
+
+/// An unbounded plane of cells.
+///
+/// Only the cells that are not empty are stored, so patterns such as
+/// spaceships and guns can grow and travel without limit, at a cost
+/// proportional to the number of living and fading cells rather than the area
+/// they cover. The terminal shows a [`Viewport`] into the universe.
+#[derive(Clone)]
+pub struct Universe {
+    cells: HashMap<(i64, i64), Cell>,
+    rule: Rule,
+    generation: usize,
+    cycles: CycleDetector<Vec<((i64, i64), u64)>>,
+    census: Census,
+    hasher: RandomState,
+}
+
+impl Universe {
+    /// Creates a universe containing the given pattern, with its top left
+    /// corner at the origin, simulated using the given rule.
+    ///
+    /// Returns an error if the rule has B0, as cells born with no neighbours
+    /// would fill the whole unbounded plane.
+    pub fn new(pattern: &Pattern, rule: Rule) -> Result<Self, String> {
+        if rule.born(0) {
+            return Err(format!(
+                "Unbounded universes do not support rules with B0, such as {rule}."
+            ));
+        }
+
+        let cells: HashMap<_, _> = pattern
+            .cells()
+            .map(|(x, y, cell)| ((x as i64, y as i64), cell))
+            .collect();
+
+        Ok(Universe {
+            census: Census::of(cells.values()),
+            cells,
+            rule,
+            generation: 0,
+            cycles: CycleDetector::default(),
+            hasher: RandomState::new(),
+        })
+    }
+
+    /// Returns a copy of this universe that detects cycles with periods up to
+    /// `max_period` (see [`Engine::cycle`]).
+    ///
+    /// Only cycles that stay in place are detected, so spaceships travelling
+    /// forever are not.
+    pub fn with_max_period(self, max_period: usize) -> Self {
+        Universe {
+            cycles: CycleDetector::new(max_period),
+            ..self
+        }
+    }
+
+    /// Advances the universe to the next generation.
+    ///
+    /// Only cells that are not empty, and the neighbours of living cells, can
+    /// change, so only those are visited.
+    pub fn next(&mut self) {
+        let neighborhood = self.rule.neighborhood();
+        let offsets = [neighborhood.offsets(0), neighborhood.offsets(1)];
+        let offsets = |y: i64| &offsets[y.rem_euclid(2) as usize];
+
+        let mut candidates: HashSet<(i64, i64)> = self.cells.keys().copied().collect();
+        for (&(x, y), _) in self.cells.iter().filter(|(_, cell)| cell.is_alive()) {
+            for &(dx, dy) in offsets(y) {
+                candidates.insert((x + dx as i64, y + dy as i64));
+            }
+        }
+
+        let palette: Vec<u32> = self.census.colors().map(|(color, _)| color).collect();
+        let mut neighbors = Vec::with_capacity(neighborhood.size());
+        let mut next = HashMap::with_capacity(self.cells.len());
+        let mut hash = 0;
+        let mut census = Census::default();
+
+        for (x, y) in candidates {
+            let cell = self.cells.get(&(x, y)).copied().unwrap_or_default();
+            let key = cell.key(&self.rule);
+            if key != 0 {
+                // Combine the hashes of cells in an order-independent way, as
+                // the map has no fixed order.
+                hash ^= self.hasher.hash_one((x, y, key));
+            }
+
+            neighbors.clear();
+            neighbors.extend(
+                offsets(y).iter().filter_map(|&(dx, dy)| {
+                    self.cells.get(&(x + dx as i64, y + dy as i64)).copied()
+                }),
+            );
+
+            let next_cell = cell.next(&neighbors, &self.rule, &palette);
+            census.count(&cell, &next_cell);
+            if !next_cell.is_empty() {
+                next.insert((x, y), next_cell);
+            }
+        }
+
+        let state = || {
+            let mut cells: Vec<_> = (self.cells.iter())
+                .map(|(&position, cell)| (position, cell.key(&self.rule)))
+                .filter(|&(_, key)| key != 0)
+                .collect();
+            cells.sort_unstable();
+            cells
+        };
+        self.cycles.record(self.generation, hash, state);
+
+        self.cells = next;
+        self.census = census;
+        self.generation += 1;
+    }
+}
+
+impl Engine for Universe {
+    fn next(&mut self) {
+        Universe::next(self);
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn census(&self) -> Census {
+        self.census.clone()
+    }
+
+    fn cycle(&self) -> Option<Cycle> {
+        self.cycles.cycle()
+    }
+
+    /// Returns the bounding box of the cells that are not empty as a pattern,
+    /// whose top left corner is the top left corner of the bounding box.
+    fn to_pattern(&self) -> Pattern {
+        let points = self
+            .cells
+            .iter()
+            .map(|(&(x, y), &cell)| (x as isize, y as isize, cell))
+            .collect();
+        Pattern::from_points(points).with_rule(self.rule.clone())
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, cell: Cell) {
+        let old = if cell.is_empty() {
+            self.cells.remove(&(x, y))
+        } else {
+            self.cells.insert((x, y), cell)
+        };
+
+        self.census.replace(&old.unwrap_or(Cell::empty()), &cell);
+        self.cycles = CycleDetector::new(self.cycles.max_period());
+    }
+
+    fn into_editable(self: Box<Self>) -> Box<dyn Engine> {
+        self
+    }
+
+    fn snapshot(&self) -> Box<dyn Engine> {
+        Box::new(self.clone())
+    }
+
+    fn view(&self, viewport: &Viewport) -> Cow<'_, Board> {
+        let mut pattern = Pattern::new(viewport.width, viewport.height);
+
+        let (origin_x, origin_y) = viewport.origin(self.rule.neighborhood().is_hexagonal());
+
+        for y in 0..viewport.height {
+            for x in 0..viewport.width {
+                let position = (origin_x + x as i64, origin_y + y as i64);
+                if let Some(&cell) = self.cells.get(&position) {
+                    pattern.set(x, y, cell);
+                }
+            }
+        }
+
+        let board = Board::new(viewport.width, viewport.height)
+            .with_rule(self.rule.clone())
+            .with_pattern(&pattern);
+        Cow::Owned(board)
+    }
+
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        let mut living = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| cell.is_alive())
+            .map(|(&position, _)| position);
+
+        let (x, y) = living.next()?;
+        Some(
+            living.fold((x, y, x, y), |(min_x, min_y, max_x, max_y), (x, y)| {
+                (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+            }),
+        )
+    }
+}
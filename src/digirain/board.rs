@@ -3,12 +3,11 @@
 
 use std::io::{Result as IoResult, Write, stdout};
 
-use crossterm::{
-    cursor::MoveTo,
-    queue,
-    style::{ContentStyle, PrintStyledContent},
+use crossterm::{cursor::MoveTo, queue, style::ContentStyle};
+use doodles::common::{
+    term::{Charset, glyph_width, queue_glyph},
+    theme::{Intensity, Theme},
 };
-use doodles::common::term::{BOLD_STYLES, DIM_STYLES};
 use rand::{
     Rng,
     distr::{Bernoulli, Distribution},
@@ -19,6 +18,12 @@ use crate::Args;
 
 const DEFAULT_ALPHABET: &str = include_str!("alphabet.txt");
 
+/// A grid of digital rain cells.
+///
+/// The alphabet may contain wide glyphs (such as full-width katakana or CJK
+/// ideographs), which cover the cell to their right when rendered. Glyphs with
+/// no width of their own, such as combining marks, are discarded from the
+/// alphabet.
 pub struct Board {
     width: usize,
     height: usize,
@@ -34,12 +39,21 @@ pub struct Cell {
 }
 
 impl Board {
-    pub fn new(width: usize, height: usize, alphabet: Option<&str>) -> Self {
-        let alphabet = alphabet
-            .unwrap_or(DEFAULT_ALPHABET)
-            .chars()
-            .filter(|ch| !ch.is_whitespace())
-            .collect::<Vec<char>>();
+    pub fn new(width: usize, height: usize, alphabet: Option<&str>, charset: Charset) -> Self {
+        let filter = |alphabet: &str| {
+            alphabet
+                .chars()
+                .filter(|ch| !ch.is_whitespace() && glyph_width(*ch).is_some())
+                .filter(|ch| !charset.is_ascii() || ch.is_ascii_graphic())
+                .collect::<Vec<char>>()
+        };
+
+        // Fall back to the ASCII part of the default alphabet if a custom
+        // alphabet has nothing the terminal can display.
+        let mut alphabet = filter(alphabet.unwrap_or(DEFAULT_ALPHABET));
+        if alphabet.is_empty() {
+            alphabet = filter(DEFAULT_ALPHABET);
+        }
 
         let buffers = (
             vec![Cell::default(); width * height],
@@ -88,7 +102,9 @@ impl Board {
 
                 if self.buffers.0[index].is_alive(args) {
                     let mut cell = self.buffers.0[index].clone();
-                    cell.content = *self.alphabet.choose(rand).unwrap();
+                    if !args.common.reduced_motion() {
+                        cell.content = *self.alphabet.choose(rand).unwrap();
+                    }
 
                     if cell.age == 0 {
                         let continue_trail = match cell.trail_length {
@@ -122,28 +138,28 @@ impl Board {
         }
     }
 
-    pub fn render(&self, args: &Args) -> IoResult<()> {
+    pub fn render(&self, args: &Args, theme: Theme) -> IoResult<()> {
         let mut stdout = stdout();
 
         for y in 0..self.height {
             queue!(stdout, MoveTo(0, y as u16))?;
 
-            for x in 0..self.width {
+            let mut x = 0;
+            while x < self.width {
                 let cell = &self.buffers.0[self.cell_index(x, y)];
-                if cell.is_alive(args) {
+                let columns = self.width - x;
+
+                x += if cell.is_alive(args) {
                     let style = if cell.age == 0 {
-                        BOLD_STYLES[args.color]
+                        theme.style(args.color, Intensity::Bold)
                     } else {
-                        DIM_STYLES[args.color]
+                        theme.style(args.color, Intensity::Dim)
                     };
 
-                    queue!(stdout, PrintStyledContent(style.apply(cell.content)))?;
+                    queue_glyph(&mut stdout, style, cell.content, columns)?
                 } else {
-                    queue!(
-                        stdout,
-                        PrintStyledContent(ContentStyle::default().apply(" "))
-                    )?;
-                }
+                    queue_glyph(&mut stdout, ContentStyle::default(), ' ', columns)?
+                };
             }
         }
 
@@ -170,7 +186,7 @@ impl Cell {
         Self {
             age: 0,
             content,
-            trail_length: trail_length,
+            trail_length,
         }
     }
 
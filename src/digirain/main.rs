@@ -43,6 +43,11 @@ pub struct Args {
     common: CommonArgs,
 
     /// Alphabet file to use.
+    ///
+    /// The file may contain any printable characters; whitespace is ignored.
+    /// Wide characters such as full-width katakana or CJK ideographs take up
+    /// two columns, while half-width katakana take up one. Characters that have
+    /// no width of their own, such as combining marks, are discarded.
     #[arg(short = 'a', long)]
     alphabet: Option<PathBuf>,
 
@@ -71,13 +76,14 @@ fn main() -> IoResult<()> {
     let args = Args::parse();
 
     let mut stdout = stdout();
+    let theme = args.common.theme();
 
     setup_term()?;
 
     let (width, height) = terminal::size()?;
     let mut rand = rand::rng();
     let alphabet = match &args.alphabet {
-        Some(path) => match fs::read_to_string(&path) {
+        Some(path) => match fs::read_to_string(path) {
             Ok(content) => Some(content),
             Err(err) => {
                 error!("Failed to read alphabet file {}: {}", path.display(), err);
@@ -87,18 +93,23 @@ fn main() -> IoResult<()> {
         None => None,
     };
 
-    let mut board = Board::new(width as usize, height as usize, alphabet.as_deref());
+    let mut board = Board::new(
+        width as usize,
+        height as usize,
+        alphabet.as_deref(),
+        args.common.charset(),
+    );
 
     loop {
         board = board.next(&args, &mut rand);
-        board.render(&args)?;
+        board.render(&args, theme)?;
 
         match args.common.wait()? {
             WaitResult::Resize(width, height) => {
                 execute!(stdout, MoveTo(0, 0), Clear(ClearType::All))?;
                 board = board.resize(width, height);
             }
-            WaitResult::Continue => continue,
+            WaitResult::Continue | WaitResult::Key(_) | WaitResult::Mouse(_) => continue,
             WaitResult::Exit => break,
         }
     }
@@ -9,7 +9,8 @@ use std::{
 use crossterm::{cursor::MoveTo, queue, style::PrintStyledContent};
 use doodles::common::{
     dir::{Direction, Directions},
-    term::BOLD_STYLES,
+    term::Charset,
+    theme::{Intensity, Theme},
 };
 use rand::Rng;
 
@@ -104,30 +105,47 @@ impl Agent {
         }
     }
 
-    pub fn render(&self, style: &RenderStyle) -> IoResult<()> {
+    pub fn render(&self, style: &RenderStyle, charset: Charset, theme: Theme) -> IoResult<()> {
         let (x, y) = self.render_position();
 
-        let s = match style {
-            RenderStyle::Smiley => "☻",
-            RenderStyle::Inchworm => match &self.state {
+        let s = match (style, charset) {
+            (RenderStyle::Smiley, Charset::Unicode) => "☻",
+            (RenderStyle::Smiley, Charset::Ascii) => "@",
+            (RenderStyle::Inchworm, Charset::Unicode) => match &self.state {
                 State::Thinking => "•",
                 State::Moving(Direction::North) | State::Moving(Direction::South) => "┃",
                 State::Moving(Direction::East) | State::Moving(Direction::West) => "━",
                 State::Halted => "•",
             },
+            (RenderStyle::Inchworm, Charset::Ascii) => match &self.state {
+                State::Thinking => "o",
+                State::Moving(Direction::North) | State::Moving(Direction::South) => "|",
+                State::Moving(Direction::East) | State::Moving(Direction::West) => "-",
+                State::Halted => "o",
+            },
 
-            RenderStyle::Turtle => match &self.dir {
+            (RenderStyle::Turtle, Charset::Unicode) => match &self.dir {
                 Direction::North => "▲",
                 Direction::East => "►",
                 Direction::South => "▼",
                 Direction::West => "◄",
             },
+            (RenderStyle::Turtle, Charset::Ascii) => match &self.dir {
+                Direction::North => "^",
+                Direction::East => ">",
+                Direction::South => "v",
+                Direction::West => "<",
+            },
         };
 
         queue!(
             stdout(),
             MoveTo(x as u16, y as u16),
-            PrintStyledContent(BOLD_STYLES[(self.color as usize) % BOLD_STYLES.len()].apply(s)),
+            PrintStyledContent(
+                theme
+                    .marked_style(self.color as usize, Intensity::Bold)
+                    .apply(s)
+            ),
         )
     }
 
@@ -84,6 +84,8 @@ const MAZE_STYLES: [MazeRenderStyle; 6] = [
 
 fn main() -> IoResult<()> {
     let args = Args::parse();
+    let charset = args.common.charset();
+    let theme = args.common.theme();
 
     setup_term()?;
 
@@ -105,7 +107,7 @@ fn main() -> IoResult<()> {
         let agent_style = args
             .agent_style
             .unwrap_or_else(|| rand.random_range(0..AGENT_STYLES.len()));
-        let agent_style = AGENT_STYLES[agent_style % AGENT_STYLES.len()].clone();
+        let agent_style = AGENT_STYLES[agent_style % AGENT_STYLES.len()];
 
         width /= 2;
         width -= 1;
@@ -120,17 +122,26 @@ fn main() -> IoResult<()> {
                 break 'build;
             }
 
-            maze.render(&maze_style, &[], &agent_style, &random_state)?;
+            maze.render(
+                &maze_style,
+                &[],
+                &agent_style,
+                &random_state,
+                charset,
+                theme,
+            )?;
 
             match args.common.wait()? {
-                WaitResult::Continue => {}
+                WaitResult::Continue | WaitResult::Key(_) | WaitResult::Mouse(_) => {}
                 WaitResult::Resize(_, _) => continue 'outer,
                 WaitResult::Exit => break 'outer,
             }
         }
 
-        let mut agents = (0..args.agents)
-            .map(|i| Agent::new(&maze, ((i + 1) % 8) as u8))
+        let mut agents = theme
+            .choose_colors(&mut rand, 1..8, args.agents)
+            .into_iter()
+            .map(|color| Agent::new(&maze, color as u8))
             .collect::<Vec<_>>();
         agents.shuffle(&mut rand);
 
@@ -143,6 +154,8 @@ fn main() -> IoResult<()> {
                 &agents[0..active_agents],
                 &agent_style,
                 &random_state,
+                charset,
+                theme,
             )?;
 
             for agent in agents.iter_mut().take(active_agents) {
@@ -159,7 +172,7 @@ fn main() -> IoResult<()> {
             }
 
             match args.common.wait()? {
-                WaitResult::Continue => {}
+                WaitResult::Continue | WaitResult::Key(_) | WaitResult::Mouse(_) => {}
                 WaitResult::Resize(_, _) => continue 'outer,
                 WaitResult::Exit => break 'outer,
             }
@@ -17,7 +17,8 @@ use crossterm::{
 use doodles::common::{
     borders::BorderStyle,
     dir::Directions,
-    term::{DIM_STYLES, STYLES},
+    term::Charset,
+    theme::{Intensity, Theme},
 };
 use rand::{Rng, seq::SliceRandom};
 
@@ -59,6 +60,8 @@ const HEDGE_CHARS: [char; 51] = [
     '⣫', '⣮', '⣯', '⣳', '⣵', '⣷', '⣹', '⣺', '⣻', '⣼', '⣽', '⣾', '⣿',
 ];
 
+const ASCII_HEDGE_CHARS: [char; 8] = ['#', '%', '&', '@', '8', '$', '*', 'B'];
+
 bitflags! {
     #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     pub struct Cell: u8 {
@@ -171,8 +174,11 @@ impl Maze {
         agents: &[Agent],
         agent_style: &AgentRenderStyle,
         random_state: &RandomState,
+        charset: Charset,
+        theme: Theme,
     ) -> IoResult<()> {
         let mut stdout = stdout();
+        let color = style.color as usize;
         self.render_bitmap();
         let bmp = self.bitmap.borrow();
         let bmp = bmp.as_ref().unwrap();
@@ -184,7 +190,7 @@ impl Maze {
                 let idx = y * bmp_width + x;
 
                 if let Some(agent) = agents.iter().find(|a| a.render_position() == (x, y)) {
-                    agent.render(agent_style)?;
+                    agent.render(agent_style, charset, theme)?;
                     continue;
                 }
 
@@ -197,8 +203,9 @@ impl Maze {
                     {
                         let cell = self.cells[self.cell_index(cell_x, cell_y)];
                         if !cell.contains(Cell::VISITED) {
-                            let style = &DIM_STYLES[style.color as usize];
-                            queue!(stdout, PrintStyledContent(style.apply('∎')))?;
+                            let style = theme.style(color, Intensity::Dim);
+                            let marker = if charset.is_ascii() { '.' } else { '∎' };
+                            queue!(stdout, PrintStyledContent(style.apply(marker)))?;
                             continue;
                         }
                     }
@@ -232,25 +239,33 @@ impl Maze {
                         y.hash(&mut hasher);
                         hasher.finish()
                     };
-                    let ch = (hash as usize) % HEDGE_CHARS.len();
+                    let hedge_chars: &[char] = match charset {
+                        Charset::Unicode => &HEDGE_CHARS,
+                        Charset::Ascii => &ASCII_HEDGE_CHARS,
+                    };
+                    let ch = (hash as usize) % hedge_chars.len();
 
                     queue!(
                         stdout,
-                        PrintStyledContent(STYLES[style.color as usize].apply(HEDGE_CHARS[ch]))
+                        PrintStyledContent(
+                            theme.style(color, Intensity::Normal).apply(hedge_chars[ch])
+                        )
                     )
                 };
 
+                let block = if charset.is_ascii() { '#' } else { '█' };
+
                 if style.outer == WallStyle::Block && (x_border || y_border) {
                     queue!(
                         stdout,
-                        PrintStyledContent(STYLES[style.color as usize].apply('█'))
+                        PrintStyledContent(theme.style(color, Intensity::Normal).apply(block))
                     )?;
                 } else if style.outer == WallStyle::Hedge && (x_border || y_border) {
                     print_hedge(x, y)?;
                 } else if style.inner == WallStyle::Block && !(x_border || y_border) {
                     queue!(
                         stdout,
-                        PrintStyledContent(STYLES[style.color as usize].apply('█'))
+                        PrintStyledContent(theme.style(color, Intensity::Normal).apply(block))
                     )?;
                 } else if style.inner == WallStyle::Hedge && !(x_border || y_border) {
                     print_hedge(x, y)?;
@@ -259,6 +274,7 @@ impl Maze {
                     let vertical_style = if y_border { style.outer } else { style.inner };
 
                     let horizontal_style = match horizontal_style {
+                        _ if charset.is_ascii() => BorderStyle::Ascii,
                         WallStyle::Solid => BorderStyle::Single,
                         WallStyle::Curved => BorderStyle::Curved,
                         WallStyle::Double => BorderStyle::Double,
@@ -267,6 +283,7 @@ impl Maze {
                     };
 
                     let vertical_style = match vertical_style {
+                        _ if charset.is_ascii() => BorderStyle::Ascii,
                         WallStyle::Solid => BorderStyle::Single,
                         WallStyle::Curved => BorderStyle::Curved,
                         WallStyle::Double => BorderStyle::Double,
@@ -277,7 +294,8 @@ impl Maze {
                     queue!(
                         stdout,
                         PrintStyledContent(
-                            STYLES[style.color as usize]
+                            theme
+                                .style(color, Intensity::Normal)
                                 .apply(dirs.border(horizontal_style, vertical_style))
                         )
                     )?;